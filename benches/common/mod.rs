@@ -0,0 +1,27 @@
+//! Timing shared by the benchmarks.
+
+use std::time::Duration;
+use std::time::Instant;
+
+/// The time per call of the closure: the best of five measurements over
+/// enough calls to take at least 20 milliseconds each.
+pub fn measure<F: FnMut()>(mut f: F) -> Duration {
+    f();
+    let mut calls = 1;
+    while {
+        let start = Instant::now();
+        for _ in 0 .. calls {
+            f();
+        }
+        start.elapsed() < Duration::from_millis(20)
+    } {
+        calls *= 2;
+    }
+    (0..5).map(|_| {
+        let start = Instant::now();
+        for _ in 0 .. calls {
+            f();
+        }
+        start.elapsed() / calls
+    }).min().unwrap()
+}
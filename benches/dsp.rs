@@ -0,0 +1,99 @@
+//! Benchmark the transforms and complex arithmetic over a range of sizes.
+//!
+//! Run with `cargo bench --bench dsp`, optionally followed by `--` and a
+//! word that the names of the benchmarks to run must contain, such as
+//! `cargo bench --bench dsp -- fdft`.
+//!
+//! Transforms are reported in GFLOPS by the convention of counting
+//! 5 _n_ log<sub>2</sub> _n_ floating-point operations for a transform of
+//! length _n_, so that the figures can be compared with those of other
+//! libraries. Complex additions count as 2 operations and multiplications as
+//! 6. Divisions are not counted, because the number of operations depends on
+//! the operands.
+//!
+//! With `--features parallel`, the `parallel` row divides the forward
+//! transform among all threads at every length. Comparing it with the
+//! `fdft` row shows from which length threads pay off, which is what the
+//! threshold of the `parallel` feature should be.
+
+extern crate adrenaline;
+
+mod common;
+
+use std::env;
+use std::hint::black_box;
+#[cfg(feature = "parallel")]
+use std::thread;
+use std::time::Duration;
+
+use adrenaline::dsp::complex::c128;
+use adrenaline::dsp::dft::fdft;
+#[cfg(feature = "parallel")]
+use adrenaline::dsp::dft::fdft_split;
+use adrenaline::dsp::dft::idft;
+
+use common::measure;
+
+fn main() {
+    // Cargo passes --bench, which is not a filter.
+    let filter: Option<String> = env::args().skip(1).find(|a| !a.starts_with("--"));
+    let enabled = |name: &str| filter.as_ref().map_or(true, |f| name.contains(f.as_str()));
+
+    println!("{:<8} {:>8} {:>14} {:>10}", "name", "n", "ns/point", "GFLOPS");
+    for log2 in 4 .. 21 {
+        let n = 1 << log2;
+        let a = data(n, 0.3);
+        let b = data(n, 1.7);
+        let mut output = vec![c128(0.0, 0.0); n];
+        let transform_flops = 5.0 * n as f64 * log2 as f64;
+
+        if enabled("fdft") {
+            let time = measure(|| fdft(black_box(&a), black_box(&mut output)));
+            report("fdft", n, time, Some(transform_flops));
+        }
+        #[cfg(feature = "parallel")]
+        {
+            if enabled("parallel") {
+                let threads = thread::available_parallelism().map_or(1, |n| n.get());
+                let time = measure(|| fdft_split(black_box(&a), black_box(&mut output), threads));
+                report("parallel", n, time, Some(transform_flops));
+            }
+        }
+        if enabled("idft") {
+            let time = measure(|| idft(black_box(&a), black_box(&mut output)));
+            report("idft", n, time, Some(transform_flops));
+        }
+        if enabled("add") {
+            let time = measure(|| elementwise(&a, &b, &mut output, |x, y| x + y));
+            report("add", n, time, Some(2.0 * n as f64));
+        }
+        if enabled("mul") {
+            let time = measure(|| elementwise(&a, &b, &mut output, |x, y| x * y));
+            report("mul", n, time, Some(6.0 * n as f64));
+        }
+        if enabled("div") {
+            let time = measure(|| elementwise(&a, &b, &mut output, |x, y| x / y));
+            report("div", n, time, None);
+        }
+    }
+}
+
+/// Nonzero complex numbers that vary in magnitude and phase.
+fn data(n: usize, seed: f64) -> Vec<c128> {
+    (0..n).map(|j| c128::from_polar(1.0 + (seed * j as f64).sin().abs(), seed * j as f64))
+        .collect()
+}
+
+fn elementwise<F>(a: &[c128], b: &[c128], output: &mut [c128], op: F)
+    where F: Fn(c128, c128) -> c128 {
+    for ((o, &x), &y) in output.iter_mut().zip(black_box(a)).zip(black_box(b)) {
+        *o = op(x, y);
+    }
+    black_box(output);
+}
+
+fn report(name: &str, n: usize, time: Duration, flops: Option<f64>) {
+    let ns = time.as_secs_f64() * 1e9;
+    let gflops = flops.map_or(String::from("-"), |f| format!("{:.2}", f / ns));
+    println!("{:<8} {:>8} {:>14.3} {:>10}", name, n, ns / n as f64, gflops);
+}
@@ -1,86 +1,494 @@
 //! Complex numbers and operations on complex numbers.
 
+use std::fmt;
+use std::iter::Product;
+use std::iter::Sum;
 use std::ops::Add;
+use std::ops::AddAssign;
 use std::ops::Div;
+use std::ops::DivAssign;
 use std::ops::Mul;
+use std::ops::MulAssign;
+use std::ops::Neg;
 use std::ops::Sub;
+use std::ops::SubAssign;
+
+use dsp::float::Float;
+
+/// A complex number consists of a real part and an imaginary part of the
+/// same floating-point type.
+///
+/// The real part is stored before the imaginary part, as in C and Fortran,
+/// so that slices of complex numbers can be processed as slices of parts.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(C)]
+pub struct Complex<T>(pub T, pub T);
 
 /// A 128-bit complex number consists of a 64-bit real part and a 64-bit
 /// imaginary part.
 #[allow(non_camel_case_types)]
-#[derive(Clone, Copy, Debug, PartialEq)]
-pub struct c128(pub f64, pub f64);
+pub type c128 = Complex<f64>;
+
+/// A 64-bit complex number consists of a 32-bit real part and a 32-bit
+/// imaginary part.
+#[allow(non_camel_case_types)]
+pub type c64 = Complex<f32>;
 
-impl c128 {
+/// The 128-bit complex number with the given real and imaginary parts.
+#[inline(always)]
+pub const fn c128(real: f64, imag: f64) -> c128 {
+    Complex(real, imag)
+}
+
+/// The 64-bit complex number with the given real and imaginary parts.
+#[inline(always)]
+pub const fn c64(real: f32, imag: f32) -> c64 {
+    Complex(real, imag)
+}
+
+impl<T: Float> Complex<T> {
     /// The complex number with the given real part and a zero imaginary part.
-    pub const fn from_real(real: f64) -> c128 {
-        c128(real, 0.0)
+    pub const fn from_real(real: T) -> Complex<T> {
+        Complex(real, T::ZERO)
     }
 
     /// The complex number with the given imaginary part and a zero real part.
-    pub const fn from_imag(real: f64) -> c128 {
-        c128(0.0, real)
+    pub const fn from_imag(imag: T) -> Complex<T> {
+        Complex(T::ZERO, imag)
     }
 
     /// The complex number at the given polar coordinates.
     #[inline(always)]
-    pub fn from_polar(r: f64, th: f64) -> c128 {
+    pub fn from_polar(r: T, th: T) -> Complex<T> {
         let (s, c) = th.sin_cos();
-        c128(r * c, r * s)
+        Complex(r * c, r * s)
     }
 
     /// The real part of the complex number.
-    pub const fn real(self) -> f64 {
+    pub const fn real(self) -> T {
         self.0
     }
 
     /// The imaginary part of the complex number.
-    pub const fn imag(self) -> f64 {
+    pub const fn imag(self) -> T {
         self.1
     }
 
     /// The complex conjugate of the complex number.
     #[inline(always)]
-    pub fn conj(self) -> c128 {
-        c128(self.0, -self.1)
+    pub fn conj(self) -> Complex<T> {
+        Complex(self.0, -self.1)
+    }
+
+    /// The squared magnitude of the complex number. This is cheaper than
+    /// [norm], but overflows for smaller magnitudes.
+    ///
+    /// [norm]: #method.norm
+    #[inline(always)]
+    pub fn norm_sqr(self) -> T {
+        self.0 * self.0 + self.1 * self.1
+    }
+
+    /// The magnitude of the complex number.
+    #[inline(always)]
+    pub fn norm(self) -> T {
+        self.0.hypot(self.1)
+    }
+
+    /// The magnitude of the complex number, the same as [norm].
+    ///
+    /// [norm]: #method.norm
+    #[inline(always)]
+    pub fn abs(self) -> T {
+        self.norm()
+    }
+
+    /// The argument of the complex number, in the interval
+    /// (&minus;&pi;, &pi;].
+    #[inline(always)]
+    pub fn arg(self) -> T {
+        self.1.atan2(self.0)
+    }
+
+    /// The polar coordinates of the complex number, as the magnitude and the
+    /// argument. This is the inverse of [from_polar].
+    ///
+    /// [from_polar]: #method.from_polar
+    #[inline(always)]
+    pub fn to_polar(self) -> (T, T) {
+        (self.norm(), self.arg())
+    }
+
+    /// The exponential function of the complex number.
+    pub fn exp(self) -> Complex<T> {
+        Complex::from_polar(self.0.exp(), self.1)
+    }
+
+    /// The principal natural logarithm of the complex number.
+    pub fn ln(self) -> Complex<T> {
+        let (r, th) = self.to_polar();
+        Complex(r.ln(), th)
+    }
+
+    /// The principal square root of the complex number.
+    pub fn sqrt(self) -> Complex<T> {
+        // Choosing the formula by the sign of the real part avoids
+        // cancellation between the magnitude and the real part.
+        let half = T::from_f64(0.5);
+        let quarter = T::from_f64(0.25);
+        let r = self.norm();
+        if r == T::ZERO {
+            return Complex(T::ZERO, self.1);
+        }
+        // The sum of the magnitude and the real part can overflow when the
+        // magnitude is near the largest finite value, so the operand is
+        // scaled down by four and the root scaled up by two.
+        if r > T::MAX * quarter && !self.0.is_infinite() && !self.1.is_infinite() {
+            return (self * quarter).sqrt() * T::from_f64(2.0);
+        }
+        if self.0 >= T::ZERO {
+            let t = ((r + self.0) * half).sqrt();
+            Complex(t, self.1 / (t + t))
+        } else {
+            let t = ((r - self.0) * half).sqrt();
+            Complex(self.1.abs() / (t + t), t.copysign(self.1))
+        }
+    }
+
+    /// The principal value of the complex number raised to a real power.
+    /// Any complex number, including zero, raised to the power zero is one.
+    pub fn powf(self, e: T) -> Complex<T> {
+        if e == T::ZERO {
+            return Complex(T::ONE, T::ZERO);
+        }
+        let (r, th) = self.to_polar();
+        Complex::from_polar(r.powf(e), th * e)
+    }
+}
+
+impl<T: Float> fmt::Display for Complex<T> {
+    /// Format the complex number as _a_+_b_i or _a_&minus;_b_i, passing the
+    /// precision on to both parts.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let sign = if self.1 < T::ZERO { '-' } else { '+' };
+        match f.precision() {
+            Some(p) => write!(f, "{:.*}{}{:.*}i", p, self.0, sign, p, self.1.abs()),
+            None    => write!(f, "{}{}{}i", self.0, sign, self.1.abs()),
+        }
     }
 }
 
-impl Add for c128 {
-    type Output = c128;
+impl<T: Float> Neg for Complex<T> {
+    type Output = Complex<T>;
 
     #[inline(always)]
-    fn add(self, rhs: c128) -> c128 {
-        c128(self.0 + rhs.0, self.1 + rhs.1)
+    fn neg(self) -> Complex<T> {
+        Complex(-self.0, -self.1)
     }
 }
 
-impl Sub for c128 {
-    type Output = c128;
+impl<T: Float> Add for Complex<T> {
+    type Output = Complex<T>;
 
     #[inline(always)]
-    fn sub(self, rhs: c128) -> c128 {
-        c128(self.0 - rhs.0, self.1 - rhs.1)
+    fn add(self, rhs: Complex<T>) -> Complex<T> {
+        Complex(self.0 + rhs.0, self.1 + rhs.1)
     }
 }
 
-impl Mul for c128 {
-    type Output = c128;
+impl<T: Float> Sub for Complex<T> {
+    type Output = Complex<T>;
 
     #[inline(always)]
-    fn mul(self, rhs: c128) -> c128 {
-        c128(self.0 * rhs.0 - self.1 * rhs.1,
-             self.0 * rhs.1 + self.1 * rhs.0)
+    fn sub(self, rhs: Complex<T>) -> Complex<T> {
+        Complex(self.0 - rhs.0, self.1 - rhs.1)
     }
 }
 
-impl Div for c128 {
-    type Output = c128;
+impl<T: Float> Mul for Complex<T> {
+    type Output = Complex<T>;
 
     #[inline(always)]
-    fn div(self, rhs: c128) -> c128 {
-        let num = self * rhs.conj();
-        let den = rhs  * rhs.conj();
-        c128(num.0 / den.0, num.1 / den.0)
+    fn mul(self, rhs: Complex<T>) -> Complex<T> {
+        Complex(self.0 * rhs.0 - self.1 * rhs.1,
+                self.0 * rhs.1 + self.1 * rhs.0)
+    }
+}
+
+impl<T: Float> Div for Complex<T> {
+    type Output = Complex<T>;
+
+    /// Divide using Smith&rsquo;s algorithm, which scales by the larger part
+    /// of the divisor instead of dividing by its squared magnitude, so that
+    /// the intermediate results neither overflow nor underflow when the
+    /// result is representable. Infinite and zero operands are handled as in
+    /// annex G of the C standard.
+    fn div(self, rhs: Complex<T>) -> Complex<T> {
+        let Complex(a, b) = self;
+        let Complex(c, d) = rhs;
+
+        let (x, y) =
+            if c.abs() >= d.abs() {
+                let r = d / c;
+                let t = T::ONE / (c + d * r);
+                if r != T::ZERO {
+                    ((a + b * r) * t, (b - a * r) * t)
+                } else {
+                    ((a + d * (b / c)) * t, (b - d * (a / c)) * t)
+                }
+            } else {
+                let r = c / d;
+                let t = T::ONE / (c * r + d);
+                if r != T::ZERO {
+                    ((a * r + b) * t, (b * r - a) * t)
+                } else {
+                    ((c * (a / d) + b) * t, (c * (b / d) - a) * t)
+                }
+            };
+
+        if !(x.is_nan() && y.is_nan()) {
+            return Complex(x, y);
+        }
+
+        let finite = |v: T| !v.is_nan() && !v.is_infinite();
+        let unit   = |v: T| if v.is_infinite() { T::ONE } else { T::ZERO }.copysign(v);
+        if c == T::ZERO && d == T::ZERO && (!a.is_nan() || !b.is_nan()) {
+            let inf = T::INFINITY.copysign(c);
+            Complex(inf * a, inf * b)
+        } else if (a.is_infinite() || b.is_infinite()) && finite(c) && finite(d) {
+            let (a, b) = (unit(a), unit(b));
+            Complex(T::INFINITY * (a * c + b * d), T::INFINITY * (b * c - a * d))
+        } else if (c.is_infinite() || d.is_infinite()) && finite(a) && finite(b) {
+            let (c, d) = (unit(c), unit(d));
+            Complex(T::ZERO * (a * c + b * d), T::ZERO * (b * c - a * d))
+        } else {
+            Complex(x, y)
+        }
+    }
+}
+
+impl<T: Float> Mul<T> for Complex<T> {
+    type Output = Complex<T>;
+
+    #[inline(always)]
+    fn mul(self, rhs: T) -> Complex<T> {
+        Complex(self.0 * rhs, self.1 * rhs)
+    }
+}
+
+impl<T: Float> Div<T> for Complex<T> {
+    type Output = Complex<T>;
+
+    #[inline(always)]
+    fn div(self, rhs: T) -> Complex<T> {
+        Complex(self.0 / rhs, self.1 / rhs)
+    }
+}
+
+macro_rules! impl_scalar_lhs {
+    ($t:ident) => {
+        impl Mul<Complex<$t>> for $t {
+            type Output = Complex<$t>;
+
+            #[inline(always)]
+            fn mul(self, rhs: Complex<$t>) -> Complex<$t> {
+                rhs * self
+            }
+        }
+    };
+}
+
+impl_scalar_lhs!(f32);
+impl_scalar_lhs!(f64);
+
+macro_rules! impl_assign {
+    ($trait:ident, $method:ident, $op:ident, $rhs:ty) => {
+        impl<T: Float> $trait<$rhs> for Complex<T> {
+            #[inline(always)]
+            fn $method(&mut self, rhs: $rhs) {
+                *self = (*self).$op(rhs);
+            }
+        }
+    };
+}
+
+impl_assign!(AddAssign, add_assign, add, Complex<T>);
+impl_assign!(SubAssign, sub_assign, sub, Complex<T>);
+impl_assign!(MulAssign, mul_assign, mul, Complex<T>);
+impl_assign!(DivAssign, div_assign, div, Complex<T>);
+impl_assign!(MulAssign, mul_assign, mul, T);
+impl_assign!(DivAssign, div_assign, div, T);
+
+impl<T: Float> Sum for Complex<T> {
+    fn sum<I>(iter: I) -> Complex<T> where I: Iterator<Item = Complex<T>> {
+        iter.fold(Complex(T::ZERO, T::ZERO), Add::add)
+    }
+}
+
+impl<'a, T: Float> Sum<&'a Complex<T>> for Complex<T> {
+    fn sum<I>(iter: I) -> Complex<T> where I: Iterator<Item = &'a Complex<T>> {
+        iter.cloned().sum()
+    }
+}
+
+impl<T: Float> Product for Complex<T> {
+    fn product<I>(iter: I) -> Complex<T> where I: Iterator<Item = Complex<T>> {
+        iter.fold(Complex(T::ONE, T::ZERO), Mul::mul)
+    }
+}
+
+impl<'a, T: Float> Product<&'a Complex<T>> for Complex<T> {
+    fn product<I>(iter: I) -> Complex<T> where I: Iterator<Item = &'a Complex<T>> {
+        iter.cloned().product()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_polar() {
+        let z = c128(-3.0, 4.0);
+        assert_eq!(z.norm_sqr(), 25.0);
+        assert_eq!(z.norm(), 5.0);
+        assert_eq!(z.abs(), 5.0);
+        let (r, th) = z.to_polar();
+        assert_aq!(c128::from_polar(r, th), z, 1e-12);
+        assert_eq!(c128(-1.0, 0.0).arg(), PI);
+    }
+
+    #[test]
+    fn test_exp_ln() {
+        assert_aq!(c128(0.0, PI).exp(), c128(-1.0, 0.0), 1e-12);
+        assert_aq!(c128(-1.0, 0.0).ln(), c128(0.0, PI), 1e-12);
+        for &z in &[c128(0.5, -2.0), c128(-3.0, 0.25), c128(1e-3, 7.0)] {
+            assert_aq!(z.ln().exp(), z, 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_sqrt_powf() {
+        assert_aq!(c128(-4.0, 0.0).sqrt(), c128(0.0, 2.0), 1e-12);
+        assert_aq!(c128(-4.0, -0.0).sqrt(), c128(0.0, -2.0), 1e-12);
+        assert_aq!(c128(0.0, 2.0).sqrt(), c128(1.0, 1.0), 1e-12);
+        assert_aq!(c128(0.0, 0.0).sqrt(), c128(0.0, 0.0), 1e-12);
+        for &z in &[c128(0.5, -2.0), c128(-3.0, 0.25), c128(-1e-3, -7.0)] {
+            assert_aq!(z.sqrt() * z.sqrt(), z, 1e-12);
+            assert_aq!(z.powf(0.5), z.sqrt(), 1e-12);
+            assert_aq!(z.powf(2.0), z * z, 1e-12);
+        }
+        assert_aq!(c128(0.0, 0.0).powf(3.0), c128(0.0, 0.0), 1e-12);
+        assert_eq!(c128(0.0, 0.0).powf(0.0), c128(1.0, 0.0));
+        assert_eq!(c128(-2.0, 3.0).powf(0.0), c128(1.0, 0.0));
+        assert!(c128(0.0, 0.0).powf(-1.0).real().is_infinite());
+    }
+
+    #[test]
+    fn test_sqrt_extreme_magnitudes() {
+        // The naive algorithm overflows the magnitude plus the real part to
+        // infinity here.
+        let check = |z: c128, expected: c128| {
+            let root = z.sqrt();
+            assert!(f64::abs(root.real() / expected.real() - 1.0) <= 1e-12, "{:?}", root);
+            assert!(f64::abs(root.imag() / expected.imag() - 1.0) <= 1e-12, "{:?}", root);
+        };
+        check(c128(1e308, 1e308), c128(1.09868411346781e154, 4.5508986056222734e153));
+        check(c128(-1e308, 1e308), c128(4.5508986056222734e153, 1.09868411346781e154));
+        check(c128(1e308, -1e308), c128(1.09868411346781e154, -4.5508986056222734e153));
+        let root = f64::sqrt(f64::MAX);
+        check(c128(f64::MAX, f64::MAX), c128(1.09868411346781 * root, 0.45508986056222734 * root));
+        check(c128(-f64::MAX, f64::MAX), c128(0.45508986056222734 * root, 1.09868411346781 * root));
+        let z = c128(f64::MAX, 0.0).sqrt();
+        assert!(f64::abs(z.real() / root - 1.0) <= 1e-12 && z.imag() == 0.0, "{:?}", z);
+        let z = c128(-f64::MAX, -0.0).sqrt();
+        assert!(f64::abs(z.imag() / root + 1.0) <= 1e-12 && z.real() == 0.0, "{:?}", z);
+        // The magnitude is far below one here.
+        check(c128(1e-308, 1e-308), c128(1.09868411346781e-154, 4.550898605622273e-155));
+        let y = 4e-320;
+        check(c128(0.0, y), c128(f64::sqrt(y / 2.0), f64::sqrt(y / 2.0)));
+    }
+
+    #[test]
+    fn test_operators() {
+        let mut z = c128(1.0, 2.0);
+        assert_eq!(-z, c128(-1.0, -2.0));
+        assert_eq!(z * 2.0, c128(2.0, 4.0));
+        assert_eq!(2.0 * z, c128(2.0, 4.0));
+        assert_eq!(z / 2.0, c128(0.5, 1.0));
+        z += c128(1.0, 1.0);
+        z -= c128(0.0, 2.0);
+        z *= c128(0.0, 1.0);
+        z /= c128(0.0, 1.0);
+        z *= 3.0;
+        z /= 2.0;
+        assert_eq!(z, c128(3.0, 1.5));
+        assert_eq!(c64(1.0, 2.0) * 2.0f32, c64(2.0, 4.0));
+    }
+
+    #[test]
+    fn test_div() {
+        assert_aq!(c128(1.0, 2.0) / c128(3.0, -4.0), c128(-0.2, 0.4), 1e-12);
+        assert_aq!(c128(-0.2, 0.4) * c128(3.0, -4.0), c128(1.0, 2.0), 1e-12);
+        assert_aq!(c128(5.0, 0.0) / c128(0.0, 2.0), c128(0.0, -2.5), 1e-12);
+    }
+
+    #[test]
+    fn test_div_extreme_magnitudes() {
+        // The naive algorithm overflows |rhs|² to infinity here.
+        assert_aq!(c128(1e300, 1e300) / c128(1e300, 1e300), c128(1.0, 0.0), 1e-12);
+        assert_aq!(c128(1e300, -1e300) / c128(0.0, 1e300), c128(-1.0, -1.0), 1e-12);
+        // The naive algorithm underflows |rhs|² to zero here.
+        assert_aq!(c128(1e-300, 1e-300) / c128(1e-300, 1e-300), c128(1.0, 0.0), 1e-12);
+        assert_aq!(c128(1e-300, 0.0) / c128(0.0, 1e-300), c128(0.0, -1.0), 1e-12);
+        // The ratio of the parts of the divisor underflows to zero here.
+        let z = c128(1e300, 1e-300) / c128(1e308, 1e-308);
+        assert!(f64::abs(z.real() - 1e-8) <= 1e-20, "{:?}", z);
+        let z = c128(1.0, 1.0) / c128(2.0, 1e-320);
+        assert_aq!(z, c128(0.5, 0.5), 1e-12);
+        let z = c128(1e-150, 1e-150) / c128(1e150, 1e-300);
+        assert!(f64::abs(z.real() / 1e-300 - 1.0) <= 1e-12, "{:?}", z);
+        assert!(f64::abs(z.imag() / 1e-300 - 1.0) <= 1e-12, "{:?}", z);
+    }
+
+    #[test]
+    fn test_div_non_finite() {
+        let inf = f64::INFINITY;
+        let nan = f64::NAN;
+
+        let z = c128(1.0, 0.0) / c128(0.0, 0.0);
+        assert!(z.real() == inf && z.imag().is_nan(), "{:?}", z);
+        let z = c128(1.0, -1.0) / c128(-0.0, 0.0);
+        assert_eq!(z, c128(-inf, inf));
+
+        let z = c128(inf, 0.0) / c128(1.0, 1.0);
+        assert!(z.real() == inf && z.imag() == -inf, "{:?}", z);
+
+        assert_eq!(c128(1.0, 1.0) / c128(inf, 0.0), c128(0.0, 0.0));
+        assert_eq!(c128(1.0, 1.0) / c128(0.0, -inf), c128(-0.0, 0.0));
+
+        let z = c128(nan, 1.0) / c128(1.0, 1.0);
+        assert!(z.real().is_nan() && z.imag().is_nan(), "{:?}", z);
+        let z = c128(1.0, 1.0) / c128(nan, 0.0);
+        assert!(z.real().is_nan() && z.imag().is_nan(), "{:?}", z);
+        let z = c128(nan, nan) / c128(0.0, 0.0);
+        assert!(z.real().is_nan() && z.imag().is_nan(), "{:?}", z);
+    }
+
+    #[test]
+    fn test_sum_product() {
+        let zs = [c128(1.0, 1.0), c128(2.0, -1.0), c128(0.0, 3.0)];
+        assert_eq!(zs.iter().sum::<c128>(), c128(3.0, 3.0));
+        assert_eq!(zs.iter().cloned().sum::<c128>(), c128(3.0, 3.0));
+        assert_eq!(zs.iter().product::<c128>(), c128(-3.0, 9.0));
+        assert_eq!(Vec::<c128>::new().into_iter().product::<c128>(), c128(1.0, 0.0));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(c128(1.0, 2.0).to_string(), "1+2i");
+        assert_eq!(c128(1.0, -2.5).to_string(), "1-2.5i");
+        assert_eq!(format!("{:.2}", c64(0.125, -1.0)), "0.12-1.00i");
     }
 }
@@ -0,0 +1,216 @@
+//! Convolution and cross-correlation of real signals, computed by
+//! multiplying spectra.
+//!
+//! Convolving signals of lengths _n_ and _m_ directly takes _O_(_nm_) time.
+//! Multiplying their zero-padded spectra takes _O_((_n_ + _m_) log(_n_ +
+//! _m_)) time instead. For a long signal and a short kernel, [overlap_add]
+//! and [overlap_save] split the signal into blocks, so that the transforms
+//! stay short and the kernel spectrum is computed only once.
+//!
+//! [overlap_add]: fn.overlap_add.html
+//! [overlap_save]: fn.overlap_save.html
+
+use dsp::complex::c128;
+use dsp::dft::fdft_real;
+use dsp::dft::idft_real;
+
+/// Compute the linear convolution of the signals, which has one fewer
+/// element than the sum of their lengths. Neither signal may be empty.
+pub fn convolve(a: &[f64], b: &[f64]) -> Vec<f64> {
+    assert!( !a.is_empty() && !b.is_empty() , "The input slice is empty" );
+    let n = a.len() + b.len() - 1;
+    let m = n.next_power_of_two();
+    let mut output = multiply(&spectrum(a, m), &spectrum(b, m), m);
+    output.truncate(n);
+    output
+}
+
+/// Compute the circular convolution of the signals, which must have the same
+/// nonzero length. The output has that length too.
+pub fn circular_convolve(a: &[f64], b: &[f64]) -> Vec<f64> {
+    assert!( !a.is_empty()         , "The input slice is empty"            );
+    assert!( a.len() == b.len()    , "The input slices differ in length"   );
+    multiply(&spectrum(a, a.len()), &spectrum(b, b.len()), a.len())
+}
+
+/// Compute the cross-correlation of the signals for every lag at which they
+/// overlap. Neither signal may be empty.
+///
+/// Element _i_ of the output is &sum;<sub>_j_</sub> _a_\[_j_ + &ell;\]
+/// _b_\[_j_\] for the lag &ell; = _i_ &minus; (_b_.len() &minus; 1). A peak
+/// at a positive lag means that _a_ lags behind _b_ by that many samples.
+pub fn correlate(a: &[f64], b: &[f64]) -> Vec<f64> {
+    let reversed: Vec<f64> = b.iter().rev().cloned().collect();
+    convolve(a, &reversed)
+}
+
+/// Compute the lag at which the cross-correlation of the signals is largest,
+/// as defined for [correlate]. Neither signal may be empty.
+///
+/// [correlate]: fn.correlate.html
+pub fn best_lag(a: &[f64], b: &[f64]) -> isize {
+    let correlation = correlate(a, b);
+    let (i, _) = correlation.iter().enumerate()
+        .fold((0, correlation[0]), |(bi, bc), (i, &c)| {
+            if c > bc { (i, c) } else { (bi, bc) }
+        });
+    i as isize - (b.len() as isize - 1)
+}
+
+/// Compute the linear convolution of a long signal with a short kernel using
+/// the overlap-add method. The result is the same as that of [convolve].
+///
+/// The signal is split into blocks that are convolved with the kernel
+/// separately. The tails of consecutive block convolutions overlap and are
+/// added together.
+///
+/// [convolve]: fn.convolve.html
+pub fn overlap_add(signal: &[f64], kernel: &[f64]) -> Vec<f64> {
+    assert!( !signal.is_empty() && !kernel.is_empty() , "The input slice is empty" );
+    let m = block_transform_len(kernel.len());
+    let block_len = m - kernel.len() + 1;
+    let kernel_spectrum = spectrum(kernel, m);
+
+    let mut output = vec![0.0; signal.len() + m];
+    for (b, block) in signal.chunks(block_len).enumerate() {
+        let convolved = multiply(&spectrum(block, m), &kernel_spectrum, m);
+        let offset = b * block_len;
+        for (o, c) in output[offset .. offset + m].iter_mut().zip(convolved) {
+            *o += c;
+        }
+    }
+    output.truncate(signal.len() + kernel.len() - 1);
+    output
+}
+
+/// Compute the linear convolution of a long signal with a short kernel using
+/// the overlap-save method. The result is the same as that of [convolve].
+///
+/// The signal is split into overlapping frames that are convolved with the
+/// kernel circularly. The first outputs of each frame are corrupted by the
+/// wrap-around and are discarded; the rest are kept as is.
+///
+/// [convolve]: fn.convolve.html
+pub fn overlap_save(signal: &[f64], kernel: &[f64]) -> Vec<f64> {
+    assert!( !signal.is_empty() && !kernel.is_empty() , "The input slice is empty" );
+    let k = kernel.len();
+    let m = block_transform_len(k);
+    let step = m - k + 1;
+    let kernel_spectrum = spectrum(kernel, m);
+
+    // Pad the signal with k - 1 leading zeros, so that the first frame
+    // produces the first outputs, and with enough trailing zeros to flush
+    // the tail of the convolution.
+    let n = signal.len() + k - 1;
+    let mut padded = vec![0.0; k - 1 + n + m];
+    padded[k - 1 .. k - 1 + signal.len()].copy_from_slice(signal);
+
+    let mut output = Vec::with_capacity(n + step);
+    let mut start = 0;
+    while output.len() < n {
+        let frame = spectrum(&padded[start .. start + m], m);
+        let convolved = multiply(&frame, &kernel_spectrum, m);
+        output.extend_from_slice(&convolved[k - 1 ..]);
+        start += step;
+    }
+    output.truncate(n);
+    output
+}
+
+/// The transform length used for blocks convolved with a kernel of the given
+/// length: a power of two large enough that most of each block is signal.
+fn block_transform_len(kernel_len: usize) -> usize {
+    usize::max(64, (4 * kernel_len).next_power_of_two())
+}
+
+/// The half spectrum of the signal, zero-padded to the given length.
+fn spectrum(signal: &[f64], len: usize) -> Vec<c128> {
+    let mut padded = vec![0.0; len];
+    padded[.. signal.len()].copy_from_slice(signal);
+    let mut spectrum = vec![c128(0.0, 0.0); len / 2 + 1];
+    fdft_real(&padded, &mut spectrum);
+    spectrum
+}
+
+/// The circular convolution of the signals of the given length with the given
+/// half spectra.
+fn multiply(a: &[c128], b: &[c128], len: usize) -> Vec<f64> {
+    let product: Vec<c128> = a.iter().zip(b).map(|(&a, &b)| a * b).collect();
+    let mut output = vec![0.0; len];
+    idft_real(&product, &mut output);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dsp::testing::Random;
+
+    fn naive_convolve(a: &[f64], b: &[f64]) -> Vec<f64> {
+        let mut output = vec![0.0; a.len() + b.len() - 1];
+        for (i, &x) in a.iter().enumerate() {
+            for (j, &y) in b.iter().enumerate() {
+                output[i + j] += x * y;
+            }
+        }
+        output
+    }
+
+    fn assert_all_aq(actual: &[f64], expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, b) in actual.iter().zip(expected) {
+            assert!(f64::abs(a - b) <= 1e-9, "{:?} ≉ {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_convolve() {
+        for &(n, m) in &[(1, 1), (1, 5), (5, 1), (7, 3), (100, 37), (1000, 1000)] {
+            let mut random = Random::new(0x9E3779B97F4A7C15);
+            let (a, b) = (random.noise(n), random.noise(m));
+            assert_all_aq(&convolve(&a, &b), &naive_convolve(&a, &b));
+        }
+    }
+
+    #[test]
+    fn test_circular_convolve() {
+        for &n in &[1, 2, 5, 8, 99] {
+            let mut random = Random::new(0x9E3779B97F4A7C15);
+            let (a, b) = (random.noise(n), random.noise(n));
+            let linear = naive_convolve(&a, &b);
+            let mut expected = linear[.. n].to_vec();
+            for (i, &x) in linear[n ..].iter().enumerate() {
+                expected[i] += x;
+            }
+            assert_all_aq(&circular_convolve(&a, &b), &expected);
+        }
+    }
+
+    #[test]
+    fn test_overlap_add_save() {
+        for &(n, m) in &[(1, 1), (10, 3), (1000, 17), (5000, 200), (3, 50)] {
+            let mut random = Random::new(0x9E3779B97F4A7C15);
+            let (a, b) = (random.noise(n), random.noise(m));
+            let expected = naive_convolve(&a, &b);
+            assert_all_aq(&overlap_add(&a, &b), &expected);
+            assert_all_aq(&overlap_save(&a, &b), &expected);
+        }
+    }
+
+    #[test]
+    fn test_correlate() {
+        let a = [1.0, 2.0, 3.0];
+        let b = [0.0, 1.0, 0.5];
+        assert_all_aq(&correlate(&a, &b), &[0.5, 2.0, 3.5, 3.0, 0.0]);
+    }
+
+    #[test]
+    fn test_best_lag() {
+        // The error rate follows the request rate with a delay of 7 samples.
+        let requests = Random::new(0xD1B54A32D192ED03).noise(200);
+        let mut errors = vec![0.0; 7];
+        errors.extend_from_slice(&requests[.. 193]);
+        assert_eq!(best_lag(&errors, &requests), 7);
+        assert_eq!(best_lag(&requests, &errors), -7);
+    }
+}
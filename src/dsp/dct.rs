@@ -0,0 +1,254 @@
+//! Discrete cosine transforms express a real signal as a sum of cosines.
+//! For smooth signals the energy is concentrated in a few coefficients, so
+//! long metric histories can be stored compactly by keeping only the largest
+//! ones.
+//!
+//! All transforms are orthonormal: they preserve the energy of the signal,
+//! DCT-III is the inverse of DCT-II, and DCT-IV is its own inverse. Each is
+//! computed with a single complex transform, using [fdft] or [idft].
+//!
+//! [fdft]: ../dft/fn.fdft.html
+//! [idft]: ../dft/fn.idft.html
+
+use std::f64::consts::PI;
+
+use dsp::complex::c128;
+use dsp::dft::fdft;
+use dsp::dft::idft;
+
+/// A signal reconstructed from some of its DCT-II coefficients.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Reconstruction {
+    /// The reconstructed signal.
+    pub signal: Vec<f64>,
+
+    /// The indices of the kept coefficients, in increasing order.
+    pub indices: Vec<usize>,
+
+    /// The root-mean-square difference between the reconstructed and the
+    /// original signal.
+    pub rms_error: f64,
+
+    /// The largest absolute difference between the reconstructed and the
+    /// original signal.
+    pub max_error: f64,
+}
+
+/// Compute the DCT-II of the input, which is
+/// _X_<sub>_k_</sub> = _s_<sub>_k_</sub> &sum;<sub>_j_</sub> _x_<sub>_j_</sub>
+/// cos(&pi;_k_(2_j_ + 1)/(2_n_)), with _s_<sub>0</sub> = &radic;(1/_n_) and
+/// _s_<sub>_k_</sub> = &radic;(2/_n_) otherwise.
+///
+/// The input must not be empty, and the output must be at least as long as
+/// the input. The first _n_ elements of the output are overwritten.
+pub fn dct2(input: &[f64], output: &mut [f64]) {
+    let n = check(input, output);
+
+    // Even samples in order followed by odd samples in reverse order turn
+    // the transform into a complex transform of the same length.
+    let mut permuted = vec![c128(0.0, 0.0); n];
+    for (j, &x) in input.iter().enumerate() {
+        let position = if j % 2 == 0 { j / 2 } else { n - 1 - j / 2 };
+        permuted[position] = c128::from_real(x);
+    }
+    let mut spectrum = vec![c128(0.0, 0.0); n];
+    fdft(&permuted, &mut spectrum);
+
+    for (k, (o, &c)) in output.iter_mut().zip(&spectrum).enumerate() {
+        let twiddle = c128::from_polar(1.0, -PI * k as f64 / (2 * n) as f64);
+        *o = (c * twiddle).real() * scale(k, n);
+    }
+}
+
+/// Compute the DCT-III of the input, which is the inverse of the [dct2]
+/// subroutine.
+///
+/// The same restrictions apply as those to the [dct2] subroutine.
+///
+/// [dct2]: fn.dct2.html
+pub fn dct3(input: &[f64], output: &mut [f64]) {
+    let n = check(input, output);
+
+    let unscaled = |k: usize| if k < n { input[k] / scale(k, n) } else { 0.0 };
+    let spectrum: Vec<c128> = (0..n).map(|k| {
+        let twiddle = c128::from_polar(1.0, PI * k as f64 / (2 * n) as f64);
+        twiddle * c128(unscaled(k), -unscaled(n - k))
+    }).collect();
+    let mut permuted = vec![c128(0.0, 0.0); n];
+    idft(&spectrum, &mut permuted);
+
+    for (j, o) in output[.. n].iter_mut().enumerate() {
+        let position = if j % 2 == 0 { j / 2 } else { n - 1 - j / 2 };
+        *o = permuted[position].real();
+    }
+}
+
+/// Compute the DCT-IV of the input, which is
+/// _X_<sub>_k_</sub> = &radic;(2/_n_) &sum;<sub>_j_</sub> _x_<sub>_j_</sub>
+/// cos(&pi;(2_j_ + 1)(2_k_ + 1)/(4_n_)). The DCT-IV is its own inverse.
+///
+/// The same restrictions apply as those to the [dct2] subroutine.
+///
+/// [dct2]: fn.dct2.html
+pub fn dct4(input: &[f64], output: &mut [f64]) {
+    let n = check(input, output);
+    let nf = n as f64;
+
+    // The phase (2j + 1)(2k + 1)/(4n) splits into 2jk/(2n), which a complex
+    // transform of the input padded to 2n computes, and terms in j or k
+    // alone.
+    let mut twisted = vec![c128(0.0, 0.0); 2 * n];
+    for (j, (t, &x)) in twisted.iter_mut().zip(input).enumerate() {
+        *t = c128::from_polar(x, -PI * j as f64 / (2.0 * nf));
+    }
+    let mut spectrum = vec![c128(0.0, 0.0); 2 * n];
+    fdft(&twisted, &mut spectrum);
+
+    let scale = f64::sqrt(2.0 / nf);
+    for (k, (o, &c)) in output[.. n].iter_mut().zip(&spectrum).enumerate() {
+        let twiddle = c128::from_polar(1.0, -PI * (2 * k + 1) as f64 / (4.0 * nf));
+        *o = (c * twiddle).real() * scale;
+    }
+}
+
+/// Reconstruct the signal from the given number of its DCT-II coefficients
+/// with the largest magnitudes, and report the error of the reconstruction.
+///
+/// Because the transform is orthonormal, the squared error is the energy
+/// of the discarded coefficients. Keeping at least as many coefficients as
+/// there are samples reconstructs the signal up to rounding. The signal
+/// must not be empty. A signal with a value that is not a number, such as a
+/// gap, has coefficients that are not numbers, and so have its
+/// reconstruction and errors.
+pub fn reconstruct_top_k(signal: &[f64], k: usize) -> Reconstruction {
+    let n = signal.len();
+    let mut coefficients = vec![0.0; n];
+    dct2(signal, &mut coefficients);
+
+    let mut indices: Vec<usize> = (0..n).collect();
+    indices.sort_by(|&a, &b| {
+        f64::abs(coefficients[b]).total_cmp(&f64::abs(coefficients[a])).then(a.cmp(&b))
+    });
+    indices.truncate(k);
+    indices.sort();
+
+    let mut kept = vec![0.0; n];
+    for &i in &indices {
+        kept[i] = coefficients[i];
+    }
+    let mut reconstructed = vec![0.0; n];
+    dct3(&kept, &mut reconstructed);
+
+    let errors = || reconstructed.iter().zip(signal).map(|(a, b)| f64::abs(a - b));
+    let rms_error = f64::sqrt(errors().map(|e| e * e).sum::<f64>() / n as f64);
+    // The maximum would skip errors that are not numbers.
+    let max_error = if rms_error.is_nan() { f64::NAN } else { errors().fold(0.0, f64::max) };
+    Reconstruction{signal: reconstructed, indices, rms_error, max_error}
+}
+
+fn check(input: &[f64], output: &[f64]) -> usize {
+    assert!( !input.is_empty()            , "The input slice is empty"        );
+    assert!( output.len() >= input.len()  , "The output slice is too small"   );
+    input.len()
+}
+
+/// The factor that makes coefficient k of the DCT-II orthonormal.
+fn scale(k: usize, n: usize) -> f64 {
+    if k == 0 { f64::sqrt(1.0 / n as f64) } else { f64::sqrt(2.0 / n as f64) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dsp::testing::signal;
+
+    const LENGTHS: [usize; 7] = [1, 2, 5, 8, 12, 17, 64];
+
+    fn assert_close(actual: &[f64], expected: &[f64]) {
+        for (a, b) in actual.iter().zip(expected) {
+            assert!(f64::abs(a - b) <= 1e-10, "{:?} ≉ {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn test_dct2_matches_definition() {
+        for &n in &LENGTHS {
+            let input = signal(n);
+            let expected: Vec<f64> = (0..n).map(|k| {
+                let sum: f64 = input.iter().enumerate()
+                    .map(|(j, x)| x * f64::cos(PI * (k * (2 * j + 1)) as f64 / (2 * n) as f64))
+                    .sum();
+                sum * scale(k, n)
+            }).collect();
+            let mut actual = vec![0.0; n];
+            dct2(&input, &mut actual);
+            assert_close(&actual, &expected);
+        }
+    }
+
+    #[test]
+    fn test_dct4_matches_definition() {
+        for &n in &LENGTHS {
+            let input = signal(n);
+            let expected: Vec<f64> = (0..n).map(|k| {
+                let sum: f64 = input.iter().enumerate()
+                    .map(|(j, x)| x * f64::cos(PI * ((2 * j + 1) * (2 * k + 1)) as f64 / (4 * n) as f64))
+                    .sum();
+                sum * f64::sqrt(2.0 / n as f64)
+            }).collect();
+            let mut actual = vec![0.0; n];
+            dct4(&input, &mut actual);
+            assert_close(&actual, &expected);
+        }
+    }
+
+    #[test]
+    fn test_inverses() {
+        for &n in &LENGTHS {
+            let input = signal(n);
+            let mut coefficients = vec![0.0; n];
+            let mut output = vec![0.0; n];
+            dct2(&input, &mut coefficients);
+            dct3(&coefficients, &mut output);
+            assert_close(&output, &input);
+            dct4(&input, &mut coefficients);
+            dct4(&coefficients, &mut output);
+            assert_close(&output, &input);
+        }
+    }
+
+    #[test]
+    fn test_reconstruct_top_k() {
+        // A smooth daily profile compresses into a few coefficients.
+        let input: Vec<f64> = (0..288).map(|j| {
+            let t = j as f64 / 288.0;
+            50.0 + 20.0 * f64::sin(2.0 * PI * t) + 5.0 * f64::cos(4.0 * PI * t)
+        }).collect();
+        let result = reconstruct_top_k(&input, 12);
+        assert_eq!(result.indices.len(), 12);
+        assert!(result.indices.contains(&0));
+        assert!(result.rms_error <= 0.2, "{}", result.rms_error);
+        assert!(result.max_error >= result.rms_error);
+
+        // The squared error is the energy of the discarded coefficients.
+        let mut coefficients = vec![0.0; 288];
+        dct2(&input, &mut coefficients);
+        let discarded: f64 = coefficients.iter().enumerate()
+            .filter(|&(i, _)| !result.indices.contains(&i))
+            .map(|(_, c)| c * c).sum();
+        assert!(f64::abs(result.rms_error * result.rms_error * 288.0 - discarded) <= 1e-9);
+
+        let exact = reconstruct_top_k(&input, 1000);
+        assert!(exact.max_error <= 1e-10, "{}", exact.max_error);
+    }
+
+    #[test]
+    fn test_reconstruct_top_k_nan() {
+        let mut input: Vec<f64> = (0..64).map(|j| f64::sin(0.3 * j as f64)).collect();
+        input[10] = f64::NAN;
+        let result = reconstruct_top_k(&input, 8);
+        assert_eq!(result.indices.len(), 8);
+        assert!(result.rms_error.is_nan());
+        assert!(result.max_error.is_nan());
+    }
+}
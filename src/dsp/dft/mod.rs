@@ -0,0 +1,687 @@
+//! Discrete Fourier transform subroutines using the Cooley&ndash;Tukey fast
+//! Fourier transform algorithm.
+//!
+//! Lengths whose only prime factors are 2, 3 and 5 are transformed with a
+//! mixed-radix decomposition. All other lengths are transformed with
+//! Bluestein&rsquo;s algorithm, which expresses the transform as a
+//! convolution that is in turn computed with a power-of-two transform.
+//!
+//! Multidimensional data is transformed along each axis in turn with the
+//! one-dimensional subroutines.
+//!
+//! With the `parallel` feature, [fdft] and [idft] divide transforms of at
+//! least 65536 elements among as many threads as the processor has cores.
+//!
+//! [fdft]: fn.fdft.html
+//! [idft]: fn.idft.html
+
+use std::f64::consts::PI;
+
+use dsp::Error;
+use dsp::complex::Complex;
+use dsp::float::Float;
+
+pub use self::nd::fdft_2d;
+pub use self::nd::fdft_nd;
+pub use self::nd::idft_2d;
+pub use self::nd::idft_nd;
+#[cfg(feature = "parallel")]
+#[doc(hidden)]
+pub use self::parallel::fdft_split;
+pub use self::plan::Direction;
+pub use self::plan::Plan;
+pub use self::real::fdft_real;
+pub use self::real::idft_real;
+
+mod nd;
+#[cfg(feature = "parallel")]
+mod parallel;
+mod plan;
+#[cfg(test)]
+mod properties;
+mod real;
+
+/// Compute the forward discrete Fourier transform of the input.
+///
+/// When calling this subroutine, you must beware of certain restrictions and
+/// liberties:
+///
+///  - The input slice must have a length _n_ &ge; 1.
+///  - The output slice must have at least _n_ elements.
+///  - The first _n_ elements of the output slice will be overwritten.
+///  - It is safe to call this subroutine with the elements of the output slice
+///    uninitialized.
+///  - This subroutine does not have any side-effects other than overwriting
+///    the elements of the output slice.
+///
+/// This subroutine panics if the restrictions are violated. Use [try_fdft]
+/// to get an error instead.
+///
+/// [try_fdft]: fn.try_fdft.html
+#[inline(always)]
+pub fn fdft<T: Float>(input: &[Complex<T>], output: &mut [Complex<T>]) {
+    if let Err(err) = try_fdft(input, output) {
+        panic!("{}", err);
+    }
+}
+
+/// Compute the inverse discrete Fourier transform of the input.
+///
+/// The same restrictions and liberties apply as those to the [fdft]
+/// subroutine.
+///
+/// [fdft]: fn.fdft.html
+#[inline(always)]
+pub fn idft<T: Float>(input: &[Complex<T>], output: &mut [Complex<T>]) {
+    if let Err(err) = try_idft(input, output) {
+        panic!("{}", err);
+    }
+}
+
+/// Compute the forward discrete Fourier transform of the input, or return an
+/// error if the restrictions of the [fdft] subroutine are violated.
+///
+/// The output slice is not modified when an error is returned.
+///
+/// [fdft]: fn.fdft.html
+#[inline(always)]
+pub fn try_fdft<T: Float>(input: &[Complex<T>], output: &mut [Complex<T>]) -> Result<(), Error> {
+    check(input.len(), output.len())?;
+    transform(input, output, |c| c);
+    Ok(())
+}
+
+/// Compute the inverse discrete Fourier transform of the input, or return an
+/// error if the restrictions of the [idft] subroutine are violated.
+///
+/// The output slice is not modified when an error is returned.
+///
+/// [idft]: fn.idft.html
+#[inline(always)]
+pub fn try_idft<T: Float>(input: &[Complex<T>], output: &mut [Complex<T>]) -> Result<(), Error> {
+    check(input.len(), output.len())?;
+    transform(input, output, |c| c.conj());
+    let nf = T::from_f64(input.len() as f64);
+    for r in &mut output[.. input.len()] {
+        *r = r.conj() / nf;
+    }
+    Ok(())
+}
+
+fn check(input: usize, output: usize) -> Result<(), Error> {
+    if input == 0 {
+        return Err(Error::EmptyInput);
+    }
+    if output < input {
+        return Err(Error::OutputTooSmall);
+    }
+    // Bluestein's algorithm needs a power-of-two transform of at least
+    // 2n - 1 elements, which must be representable.
+    if !is_smooth(input) &&
+       input.checked_mul(2).and_then(|m| (m - 1).checked_next_power_of_two()).is_none() {
+        return Err(Error::UnsupportedLength);
+    }
+    Ok(())
+}
+
+/// Compute the forward discrete Fourier transform of the data in place.
+///
+/// This subroutine is iterative rather than recursive, and does not need an
+/// output slice. The data slice must have a length _n_ &ge; 1. For lengths
+/// whose only prime factors are 2, 3 and 5 this subroutine does not allocate;
+/// for other lengths it allocates scratch space.
+pub fn fdft_in_place<T: Float>(data: &mut [Complex<T>]) {
+    assert!( !data.is_empty() , "The input slice is empty" );
+    transform_in_place(data, -1.0);
+}
+
+/// Compute the inverse discrete Fourier transform of the data in place.
+///
+/// The same restrictions and liberties apply as those to the
+/// [fdft_in_place] subroutine.
+///
+/// [fdft_in_place]: fn.fdft_in_place.html
+pub fn idft_in_place<T: Float>(data: &mut [Complex<T>]) {
+    assert!( !data.is_empty() , "The input slice is empty" );
+    transform_in_place(data, 1.0);
+    let nf = T::from_f64(data.len() as f64);
+    for r in data {
+        *r /= nf;
+    }
+}
+
+#[inline(always)]
+fn transform<T, F>(i: &[Complex<T>], o: &mut [Complex<T>], f: F)
+    where T: Float, F: Copy + Send + Sync + Fn(Complex<T>) -> Complex<T> {
+    if is_smooth(i.len()) {
+        let w = forward_twiddles(i.len());
+        smooth_transform(i, o, f, &w);
+    } else {
+        bluestein(i, o, f);
+    }
+}
+
+/// The complex number e<sup>i&theta;</sup>. The angle is given and the sine
+/// and cosine are computed in `f64` for accuracy at any precision.
+#[inline(always)]
+fn cis<T: Float>(th: f64) -> Complex<T> {
+    let (s, c) = th.sin_cos();
+    Complex(T::from_f64(c), T::from_f64(s))
+}
+
+/// Whether the only prime factors of _n_ are 2, 3 and 5.
+fn is_smooth(mut n: usize) -> bool {
+    for &p in &[2, 3, 5] {
+        while n % p == 0 {
+            n /= p;
+        }
+    }
+    n == 1
+}
+
+/// The smallest of 2, 3 and 5 that divides _n_, assuming there is one.
+#[inline(always)]
+fn radix(n: usize) -> usize {
+    if n % 2 == 0 { 2 } else if n % 3 == 0 { 3 } else { 5 }
+}
+
+fn transform_in_place<T: Float>(data: &mut [Complex<T>], sign: f64) {
+    let n = data.len();
+    if !is_smooth(n) {
+        let input = data.to_vec();
+        if sign < 0.0 {
+            bluestein(&input, data, |c| c);
+        } else {
+            bluestein(&input, data, |c| c.conj());
+            for r in data {
+                *r = r.conj();
+            }
+        }
+        return;
+    }
+
+    // Permute the data by following each cycle of the permutation from its
+    // smallest index, so that no bookkeeping of visited indices is needed.
+    let reversal = DigitReversal::new(n);
+    for i in 0..n {
+        let mut j = reversal.apply(i);
+        while j > i {
+            j = reversal.apply(j);
+        }
+        if j < i {
+            continue;
+        }
+        let first = data[i];
+        let mut current = i;
+        loop {
+            let next = reversal.apply(current);
+            if next == i {
+                data[current] = first;
+                break;
+            }
+            data[current] = data[next];
+            current = next;
+        }
+    }
+
+    butterflies(data, Twiddles::Computed(sign));
+}
+
+/// The largest of 2, 3 and 5 that divides _n_, assuming there is one. This is
+/// the radix of the innermost step of the decomposition chosen by [radix].
+///
+/// [radix]: fn.radix.html
+#[inline(always)]
+fn inner_radix(n: usize) -> usize {
+    if n % 5 == 0 { 5 } else if n % 3 == 0 { 3 } else { 2 }
+}
+
+/// The permutation that the iterative algorithm applies before the butterfly
+/// stages. It maps each output position to the input index that is read
+/// there, by reversing the digits of the position in the mixed radix given
+/// by the decomposition. For powers of two, this is bit reversal.
+#[derive(Clone, Copy)]
+struct DigitReversal {
+    /// The exponents of 5, 3 and 2 in the length, in the order of the
+    /// digits from least significant: the innermost decomposition steps
+    /// have radix 5, the outermost radix 2.
+    exponents: [u32; 3],
+}
+
+impl DigitReversal {
+    fn new(mut n: usize) -> DigitReversal {
+        let mut exponents = [0; 3];
+        for (e, &p) in exponents.iter_mut().zip(&[5, 3, 2]) {
+            while n % p == 0 {
+                n /= p;
+                *e += 1;
+            }
+        }
+        DigitReversal{exponents}
+    }
+
+    /// The input index for the given output position. The digits are taken
+    /// off the position from least significant and put onto the index from
+    /// most significant, dividing by constants so that this is cheap.
+    #[inline(always)]
+    fn apply(self, mut position: usize) -> usize {
+        let mut index = 0;
+        macro_rules! digits {
+            ($p:expr, $count:expr) => {
+                for _ in 0 .. $count {
+                    index = index * $p + position % $p;
+                    position /= $p;
+                }
+            };
+        }
+        digits!(5, self.exponents[0]);
+        digits!(3, self.exponents[1]);
+        digits!(2, self.exponents[2]);
+        index
+    }
+}
+
+/// Where the iterative algorithm gets its twiddle factors from.
+#[derive(Clone, Copy)]
+enum Twiddles<'a, T: 'a> {
+    /// A table of the factors e<sup>&plusmn;2&pi;ik/n</sup> for all
+    /// _k_ &lt; _n_.
+    Table(&'a [Complex<T>]),
+
+    /// No table: the factors are computed as they are needed, with the given
+    /// sign of the exponent.
+    Computed(f64),
+}
+
+/// The twiddle factors e<sup>&plusmn;2&pi;ik/n</sup> for _k_ = 0, 1, 2,
+/// &hellip; in turn. Each is the previous one times the first, and every
+/// few steps it is computed from scratch, so that rounding errors do not
+/// accumulate.
+struct Rotation<T> {
+    angle: f64,
+    k: usize,
+    current: Complex<T>,
+    step: Complex<T>,
+}
+
+impl<T: Float> Rotation<T> {
+    /// How many factors are computed by multiplication before one is
+    /// computed from scratch.
+    const RESYNC: usize = 32;
+
+    fn new(sign: f64, n: usize) -> Rotation<T> {
+        let angle = sign * 2.0 * PI / n as f64;
+        Rotation{angle, k: 0, current: Complex(T::ONE, T::ZERO), step: cis(angle)}
+    }
+
+    #[inline(always)]
+    fn next(&mut self) -> Complex<T> {
+        let w = self.current;
+        self.k += 1;
+        self.current =
+            if self.k % Self::RESYNC == 0 { cis(self.angle * self.k as f64) }
+            else { w * self.step };
+        w
+    }
+}
+
+/// Perform the butterfly stages of the iterative mixed-radix algorithm on
+/// data that is already in digit-reversed order, innermost decomposition
+/// first.
+#[inline(always)]
+fn butterflies<T: Float>(data: &mut [Complex<T>], twiddles: Twiddles<T>) {
+    let n = data.len();
+    let mut m = 1;
+    while m < n {
+        let p      = inner_radix(n / m);
+        let span   = p * m;
+        let stride = n / span;
+
+        // The p-th roots of unity, which every butterfly of the stage uses.
+        let mut roots = [Complex(T::ZERO, T::ZERO); 5];
+        for (j, root) in roots[..p].iter_mut().enumerate() {
+            *root = match twiddles {
+                Twiddles::Table(w)       => w[j * (n/p)],
+                Twiddles::Computed(sign) => cis(sign*2.0*PI*j as f64/p as f64),
+            };
+        }
+
+        for block in data.chunks_mut(span) {
+            let mut t = [Complex(T::ZERO, T::ZERO); 5];
+            match twiddles {
+                Twiddles::Table(w) if p == 2 => {
+                    let (lo, hi) = block.split_at_mut(m);
+                    radix2(lo, hi, w, stride);
+                },
+                Twiddles::Table(w) => for k in 0..m {
+                    for (q, tq) in t[..p].iter_mut().enumerate() {
+                        *tq = w[q * k * stride] * block[q*m + k];
+                    }
+                    butterfly(block, &t[..p], &roots, m, k);
+                },
+                Twiddles::Computed(sign) if p == 2 => {
+                    let mut rotation = Rotation::new(sign, span);
+                    let (lo, hi) = block.split_at_mut(m);
+                    for (a, b) in lo.iter_mut().zip(hi) {
+                        let t = rotation.next() * *b;
+                        let ak = *a;
+                        *a = ak + t;
+                        *b = ak - t;
+                    }
+                },
+                Twiddles::Computed(sign) => {
+                    let mut rotation = Rotation::new(sign, span);
+                    for k in 0..m {
+                        let wk = rotation.next();
+                        let mut wq = Complex(T::ONE, T::ZERO);
+                        for (q, tq) in t[..p].iter_mut().enumerate() {
+                            *tq = wq * block[q*m + k];
+                            wq *= wk;
+                        }
+                        butterfly(block, &t[..p], &roots, m, k);
+                    }
+                },
+            }
+        }
+        m = span;
+    }
+}
+
+/// Perform the radix-2 butterflies _a_<sub>_k_</sub> &larr;
+/// _a_<sub>_k_</sub> + _w_<sub>_ks_</sub>_b_<sub>_k_</sub> and
+/// _b_<sub>_k_</sub> &larr; _a_<sub>_k_</sub> &minus;
+/// _w_<sub>_ks_</sub>_b_<sub>_k_</sub> for the elements of the low and high
+/// halves, with the twiddle factors _w_ read at the stride _s_.
+#[inline(always)]
+fn radix2<T: Float>(lo: &mut [Complex<T>], hi: &mut [Complex<T>],
+                    twiddles: &[Complex<T>], stride: usize) {
+    for (k, (a, b)) in lo.iter_mut().zip(hi.iter_mut()).enumerate() {
+        let t = twiddles[k * stride] * *b;
+        let ak = *a;
+        *a = ak + t;
+        *b = ak - t;
+    }
+}
+
+/// Take the naive transform of the twiddled elements _t_ of butterfly _k_,
+/// given the roots of unity of its length, into the elements of the block
+/// that they came from.
+#[inline(always)]
+fn butterfly<T: Float>(block: &mut [Complex<T>], t: &[Complex<T>], roots: &[Complex<T>],
+                       m: usize, k: usize) {
+    let p = t.len();
+    for j in 0..p {
+        let mut sum = t[0];
+        for (q, &tq) in t.iter().enumerate().skip(1) {
+            sum += roots[q*j % p] * tq;
+        }
+        block[j*m + k] = sum;
+    }
+}
+
+/// Compute the transform of the input, whose length must be smooth, with the
+/// recursive algorithm. With the `parallel` feature, large transforms are
+/// divided among several threads.
+fn smooth_transform<T, F>(i: &[Complex<T>], o: &mut [Complex<T>], f: F, w: &[Complex<T>])
+    where T: Float, F: Copy + Send + Sync + Fn(Complex<T>) -> Complex<T> {
+    #[cfg(feature = "parallel")]
+    {
+        if i.len() >= parallel::THRESHOLD {
+            parallel::fft(i, o, i.len(), 1, f, w, parallel::threads());
+            return;
+        }
+    }
+    unsafe { fft(i, o, i.len(), 1, f, w); }
+}
+
+/// The twiddle factors e<sup>&minus;2&pi;ik/n</sup> for all _k_ &lt; _n_,
+/// which the recursive algorithm reads for every sub-transform at a stride.
+fn forward_twiddles<T: Float>(n: usize) -> Vec<Complex<T>> {
+    #[cfg(feature = "parallel")]
+    {
+        if n >= parallel::THRESHOLD {
+            return parallel::forward_twiddles(n, parallel::threads());
+        }
+    }
+    let nf = n as f64;
+    (0..n).map(|k| cis(-2.0*PI*k as f64/nf)).collect()
+}
+
+/// Compute the transform of _n_ elements of the input, taken at a stride of
+/// _s_, into the first _n_ elements of the output. The twiddle factors are
+/// those of the outermost transform, as computed by [forward_twiddles].
+///
+/// [forward_twiddles]: fn.forward_twiddles.html
+unsafe fn fft<T, F>(i: &[Complex<T>], o: &mut [Complex<T>], n: usize, s: usize, f: F,
+                    w: &[Complex<T>])
+    where T: Float, F: Copy + Fn(Complex<T>) -> Complex<T> {
+    macro_rules! i { [$offset:expr] => { *i.get_unchecked    ($offset) }; }
+    macro_rules! o { [$offset:expr] => { *o.get_unchecked_mut($offset) }; }
+    macro_rules! w { [$offset:expr] => { *w.get_unchecked    ($offset) }; }
+
+    if n == 1 {
+        o![0] = f(i![0]);
+        return;
+    }
+
+    let p = radix(n);
+    let m = n / p;
+    for q in 0..p {
+        fft(&i![q*s..], &mut o![q*m..], m, p*s, f, w);
+    }
+
+    // The twiddle factor e^(-2πik/n) is at index k * step.
+    let step = w.len() / n;
+    if p == 2 {
+        let (lo, hi) = o[..n].split_at_mut(m);
+        radix2(lo, hi, w, step);
+        return;
+    }
+
+    // Generic radix-p butterfly: twiddle the p sub-transform outputs, then
+    // take their naive p-point transform.
+    let mut t = [Complex(T::ZERO, T::ZERO); 5];
+    for k in 0..m {
+        for (q, tq) in t[..p].iter_mut().enumerate() {
+            *tq = w![q*k*step] * o![q*m+k];
+        }
+        for j in 0..p {
+            let mut sum = t[0];
+            for (q, &tq) in t[..p].iter().enumerate().skip(1) {
+                sum += w![(q*j % p)*m*step] * tq;
+            }
+            o![j*m+k] = sum;
+        }
+    }
+}
+
+/// Bluestein&rsquo;s algorithm rewrites _nk_ as
+/// (_n_&sup2; + _k_&sup2; &minus; (_k_ &minus; _n_)&sup2;) / 2, which turns
+/// the transform into a convolution with a chirp. The convolution is computed
+/// with power-of-two transforms of at least 2_n_ &minus; 1 elements.
+fn bluestein<T, F>(i: &[Complex<T>], o: &mut [Complex<T>], f: F)
+    where T: Float, F: Copy + Send + Sync + Fn(Complex<T>) -> Complex<T> {
+    let n = i.len();
+    let m = (2*n - 1).next_power_of_two();
+
+    // The exponent k² is reduced modulo 2n to keep the angles small, which
+    // matters for accuracy when n is large.
+    let mut chirp = Vec::with_capacity(n);
+    let mut kk = 0;
+    for k in 0..n {
+        let (kkf, nf) = (kk as f64, n as f64);
+        chirp.push(cis(-PI*kkf/nf));
+        kk = (kk + 2*k + 1) % (2*n);
+    }
+
+    let mut a = vec![Complex(T::ZERO, T::ZERO); m];
+    let mut b = vec![Complex(T::ZERO, T::ZERO); m];
+    for k in 0..n {
+        a[k] = f(i[k]) * chirp[k];
+    }
+    b[0] = chirp[0].conj();
+    for k in 1..n {
+        b[k  ] = chirp[k].conj();
+        b[m-k] = chirp[k].conj();
+    }
+
+    let w = forward_twiddles(m);
+    let mut fa = vec![Complex(T::ZERO, T::ZERO); m];
+    let mut fb = vec![Complex(T::ZERO, T::ZERO); m];
+    smooth_transform(&a, &mut fa, |c| c, &w);
+    smooth_transform(&b, &mut fb, |c| c, &w);
+    for k in 0..m {
+        a[k] = fa[k] * fb[k];
+    }
+    smooth_transform(&a, &mut fa, |c| c.conj(), &w);
+
+    let mf = T::from_f64(m as f64);
+    for k in 0..n {
+        o[k] = chirp[k] * fa[k].conj() / mf;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dsp::complex::c128;
+    use dsp::complex::c64;
+    use dsp::testing::complex_signal as signal;
+    use dsp::testing::naive_dft;
+
+    #[test]
+    fn test_fdft() {
+        let     input  = [c128(1.00,  0.00), c128(1.00,  0.00),
+                          c128(1.00,  0.00), c128(1.00,  0.00),
+                          c128(0.00,  0.00), c128(0.00,  0.00),
+                          c128(0.00,  0.00), c128(0.00,  0.00)];
+        let mut output = [c128(0.0, 0.0); 8];
+        fdft(&input, &mut output);
+        assert_aq!(output[0], c128( 4.00 ,  0.00 ), 0.01);
+        assert_aq!(output[1], c128( 1.00 , -2.41 ), 0.01);
+        assert_aq!(output[2], c128( 0.00 ,  0.00 ), 0.01);
+        assert_aq!(output[3], c128( 1.00 , -0.41 ), 0.01);
+        assert_aq!(output[4], c128( 0.00 ,  0.00 ), 0.01);
+        assert_aq!(output[5], c128( 0.99 ,  0.41 ), 0.01);
+        assert_aq!(output[6], c128( 0.00 ,  0.00 ), 0.01);
+        assert_aq!(output[7], c128( 0.99 ,  2.41 ), 0.01);
+    }
+
+    #[test]
+    fn test_idft() {
+        let     input  = [c128(4.00,  0.00), c128(1.00, -2.41),
+                          c128(0.00,  0.00), c128(1.00, -0.41),
+                          c128(0.00,  0.00), c128(0.99,  0.41),
+                          c128(0.00,  0.00), c128(0.99,  2.41)];
+        let mut output = [c128(0.0, 0.0); 8];
+        idft(&input, &mut output);
+        assert_aq!(output[0], c128( 1.00 ,  0.00 ), 0.01);
+        assert_aq!(output[1], c128( 1.00 ,  0.00 ), 0.01);
+        assert_aq!(output[2], c128( 1.00 ,  0.00 ), 0.01);
+        assert_aq!(output[3], c128( 1.00 ,  0.00 ), 0.01);
+        assert_aq!(output[4], c128( 0.00 ,  0.00 ), 0.01);
+        assert_aq!(output[5], c128( 0.00 ,  0.00 ), 0.01);
+        assert_aq!(output[6], c128( 0.00 ,  0.00 ), 0.01);
+        assert_aq!(output[7], c128( 0.00 ,  0.00 ), 0.01);
+    }
+
+    #[test]
+    fn test_fdft_c64() {
+        for &n in &[8, 12, 97, 1440] {
+            let     input    = signal(n);
+            let     narrowed : Vec<c64> = input.iter().map(|c| c64(c.0 as f32, c.1 as f32)).collect();
+            let mut expected = vec![c128(0.0, 0.0); n];
+            let mut actual   = vec![c64(0.0, 0.0); n];
+            fdft(&input, &mut expected);
+            fdft(&narrowed, &mut actual);
+            for (a, b) in actual.iter().zip(&expected) {
+                let tolerance = 1e-5 * n as f64;
+                assert!(f64::abs(a.real() as f64 - b.real()) <= tolerance, "n = {}: {:?} ≉ {:?}", n, a, b);
+                assert!(f64::abs(a.imag() as f64 - b.imag()) <= tolerance, "n = {}: {:?} ≉ {:?}", n, a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_try_fdft() {
+        let mut output = [c128(0.0, 0.0); 4];
+        assert_eq!(try_fdft(&[], &mut output), Err(Error::EmptyInput));
+        assert_eq!(try_idft(&[], &mut output), Err(Error::EmptyInput));
+        assert_eq!(try_fdft(&[c128(1.0, 0.0); 5], &mut output), Err(Error::OutputTooSmall));
+        assert_eq!(try_idft(&[c128(1.0, 0.0); 5], &mut output), Err(Error::OutputTooSmall));
+        assert_eq!(output, [c128(0.0, 0.0); 4]);
+        assert_eq!(try_fdft(&[c128(1.0, 0.0); 3], &mut output), Ok(()));
+        assert_aq!(output[0], c128(3.0, 0.0), 0.01);
+    }
+
+    #[test]
+    fn test_check_unsupported_length() {
+        assert_eq!(check(usize::MAX, usize::MAX), Err(Error::UnsupportedLength));
+        assert_eq!(check(1 << 40, 1 << 40), Ok(()));
+    }
+
+    #[test]
+    #[should_panic(expected = "The output slice is too small")]
+    fn test_fdft_output_too_small() {
+        fdft(&[c128(1.0, 0.0); 2], &mut [c128(0.0, 0.0); 1]);
+    }
+
+    #[test]
+    fn test_in_place() {
+        for &n in &[1, 8, 12, 30, 97, 100, 1024, 1440] {
+            let     input    = signal(n);
+            let mut expected = vec![c128(0.0, 0.0); n];
+            let mut data     = input.clone();
+            fdft(&input, &mut expected);
+            fdft_in_place(&mut data);
+            for (a, b) in data.iter().zip(&expected) {
+                assert_aq!(*a, *b, 1e-9, "n = {}: ", n);
+            }
+            idft_in_place(&mut data);
+            for (a, b) in data.iter().zip(&input) {
+                assert_aq!(*a, *b, 1e-9, "n = {}: ", n);
+            }
+        }
+    }
+
+    #[test]
+    fn test_in_place_large() {
+        // Long stages compute many twiddle factors by multiplication.
+        let n = 46080;
+        let     input    = signal(n);
+        let mut expected = vec![c128(0.0, 0.0); n];
+        let mut data     = input.clone();
+        fdft(&input, &mut expected);
+        fdft_in_place(&mut data);
+        let scale = expected.iter().map(|c| c.norm()).fold(0.0, f64::max);
+        for (a, b) in data.iter().zip(&expected) {
+            assert!((*a - *b).norm() <= 1e-13 * scale, "{:?} ≉ {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_fdft_non_power_of_two() {
+        for &n in &[1, 2, 3, 5, 6, 7, 12, 13, 30, 97, 100, 360, 1440] {
+            let     input  = signal(n);
+            let mut output = vec![c128(0.0, 0.0); n];
+            fdft(&input, &mut output);
+            for (&a, b) in output.iter().zip(naive_dft(&input)) {
+                assert_aq!(a, b, 1e-6, "n = {}: ", n);
+            }
+        }
+    }
+
+    #[test]
+    fn test_idft_non_power_of_two() {
+        for &n in &[3, 6, 11, 100, 1440, 3600] {
+            let     input    = signal(n);
+            let mut spectrum = vec![c128(0.0, 0.0); n];
+            let mut output   = vec![c128(0.0, 0.0); n];
+            fdft(&input, &mut spectrum);
+            idft(&spectrum, &mut output);
+            for (&a, &b) in output.iter().zip(&input) {
+                assert_aq!(a, b, 1e-9, "n = {}: ", n);
+            }
+        }
+    }
+}
@@ -0,0 +1,178 @@
+//! Transforms of multidimensional data.
+//!
+//! The data is stored in row-major order: the last axis varies fastest. The
+//! transform along each axis in turn is the multidimensional transform, so
+//! every line along every axis is transformed with a [Plan] for the length
+//! of that axis.
+//!
+//! [Plan]: struct.Plan.html
+
+use dsp::complex::Complex;
+use dsp::dft::Direction;
+use dsp::dft::Plan;
+use dsp::float::Float;
+
+/// Compute the forward discrete Fourier transform of the row-major data in
+/// place, along every axis of the given shape.
+///
+/// When calling this subroutine, you must beware of certain restrictions and
+/// liberties:
+///
+///  - The shape must have at least one axis, and every axis a length
+///    _n_ &ge; 1.
+///  - The data slice must have as many elements as the product of the
+///    lengths of the axes.
+///  - This subroutine allocates a plan for each distinct length of the
+///    axes, and scratch space for two lines along the longest axis.
+pub fn fdft_nd<T: Float>(data: &mut [Complex<T>], shape: &[usize]) {
+    transform_nd(data, shape, Direction::Forward);
+}
+
+/// Compute the inverse discrete Fourier transform of the row-major data in
+/// place, along every axis of the given shape.
+///
+/// The same restrictions and liberties apply as those to the [fdft_nd]
+/// subroutine.
+///
+/// [fdft_nd]: fn.fdft_nd.html
+pub fn idft_nd<T: Float>(data: &mut [Complex<T>], shape: &[usize]) {
+    transform_nd(data, shape, Direction::Inverse);
+}
+
+/// Compute the forward discrete Fourier transform of the row-major matrix
+/// in place. This is [fdft_nd] with the shape [rows, columns].
+///
+/// [fdft_nd]: fn.fdft_nd.html
+pub fn fdft_2d<T: Float>(data: &mut [Complex<T>], rows: usize, columns: usize) {
+    fdft_nd(data, &[rows, columns]);
+}
+
+/// Compute the inverse discrete Fourier transform of the row-major matrix
+/// in place. This is [idft_nd] with the shape [rows, columns].
+///
+/// [idft_nd]: fn.idft_nd.html
+pub fn idft_2d<T: Float>(data: &mut [Complex<T>], rows: usize, columns: usize) {
+    idft_nd(data, &[rows, columns]);
+}
+
+fn transform_nd<T: Float>(data: &mut [Complex<T>], shape: &[usize], direction: Direction) {
+    assert!( !shape.is_empty()                          , "The shape is empty"                   );
+    assert!( shape.iter().all(|&n| n >= 1)              , "The shape has an empty axis"          );
+    assert!( shape.iter().product::<usize>() == data.len() , "The shape does not match the data" );
+
+    let mut plans: Vec<Plan<T>> = Vec::new();
+    let mut line = Vec::new();
+    let mut transformed = Vec::new();
+    let mut scratch = Vec::new();
+    for (axis, &n) in shape.iter().enumerate() {
+        if !plans.iter().any(|plan| plan.len() == n) {
+            plans.push(Plan::new(n, direction));
+        }
+        let plan = plans.iter().find(|plan| plan.len() == n).unwrap();
+        if scratch.len() < plan.scratch_len() {
+            scratch.resize(plan.scratch_len(), Complex(T::ZERO, T::ZERO));
+        }
+
+        line.clear();
+        line.resize(n, Complex(T::ZERO, T::ZERO));
+        // The distance between consecutive elements along the axis.
+        let stride: usize = shape[axis + 1 ..].iter().product();
+        if stride == 1 {
+            for chunk in data.chunks_mut(n) {
+                line.copy_from_slice(chunk);
+                plan.execute_with_scratch(&line, chunk, &mut scratch);
+            }
+            continue;
+        }
+
+        transformed.clear();
+        transformed.resize(n, Complex(T::ZERO, T::ZERO));
+        for block in data.chunks_mut(n * stride) {
+            for offset in 0 .. stride {
+                for (l, x) in line.iter_mut().zip(block[offset ..].iter().step_by(stride)) {
+                    *l = *x;
+                }
+                plan.execute_with_scratch(&line, &mut transformed, &mut scratch);
+                for (t, x) in transformed.iter().zip(block[offset ..].iter_mut().step_by(stride)) {
+                    *x = *t;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+    use dsp::complex::c128;
+    use dsp::dft::fdft_in_place;
+    use dsp::testing::complex_signal;
+
+    /// The transform computed straight from the definition.
+    fn naive_2d(input: &[c128], rows: usize, columns: usize) -> Vec<c128> {
+        (0 .. rows * columns).map(|index| {
+            let (u, v) = (index / columns, index % columns);
+            input.iter().enumerate().map(|(j, &x)| {
+                let (r, c) = (j / columns, j % columns);
+                let phase = (u * r) as f64 / rows as f64 + (v * c) as f64 / columns as f64;
+                x * c128::from_polar(1.0, -2.0 * PI * phase)
+            }).sum()
+        }).collect()
+    }
+
+    #[test]
+    fn test_fdft_2d() {
+        for &(rows, columns) in &[(1, 1), (1, 8), (8, 1), (4, 6), (5, 7), (12, 10)] {
+            let input = complex_signal(rows * columns);
+            let mut actual = input.clone();
+            fdft_2d(&mut actual, rows, columns);
+            let expected = naive_2d(&input, rows, columns);
+            for (a, b) in actual.iter().zip(&expected) {
+                assert!((*a - *b).norm() <= 1e-9, "{}×{}: {:?} ≉ {:?}", rows, columns, a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_roundtrip_nd() {
+        let shape = [3, 4, 5, 2];
+        let input = complex_signal(120);
+        let mut actual = input.clone();
+        fdft_nd(&mut actual, &shape);
+        idft_nd(&mut actual, &shape);
+        for (a, b) in actual.iter().zip(&input) {
+            assert!((*a - *b).norm() <= 1e-12, "{:?} ≉ {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_separable() {
+        // A 3-D transform equals a 2-D transform of each plane followed by
+        // transforms along the first axis.
+        let shape = [4, 3, 5];
+        let input = complex_signal(60);
+        let mut expected = input.clone();
+        for plane in expected.chunks_mut(15) {
+            fdft_2d(plane, 3, 5);
+        }
+        for offset in 0 .. 15 {
+            let mut line: Vec<c128> = (0..4).map(|r| expected[r * 15 + offset]).collect();
+            fdft_in_place(&mut line);
+            for (r, l) in line.into_iter().enumerate() {
+                expected[r * 15 + offset] = l;
+            }
+        }
+        let mut actual = input;
+        fdft_nd(&mut actual, &shape);
+        for (a, b) in actual.iter().zip(&expected) {
+            assert!((*a - *b).norm() <= 1e-12, "{:?} ≉ {:?}", a, b);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "The shape does not match the data")]
+    fn test_shape_mismatch() {
+        fdft_2d(&mut complex_signal(10), 3, 4);
+    }
+}
@@ -0,0 +1,199 @@
+//! Multi-threaded transforms of large inputs, enabled by the `parallel`
+//! feature.
+//!
+//! The recursive algorithm splits a transform of length _n_ into _p_
+//! independent sub-transforms of length _n_/_p_, which run on separate
+//! threads, followed by _n_/_p_ independent butterflies, which are divided
+//! among the threads. The sub-transforms are split further as long as they
+//! are large and threads are left. The twiddle factors are computed on
+//! several threads as well.
+
+use std::f64::consts::PI;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::thread;
+
+use dsp::complex::Complex;
+use dsp::dft::cis;
+use dsp::dft::fft as sequential_fft;
+use dsp::dft::is_smooth;
+use dsp::dft::radix;
+use dsp::dft::radix2;
+use dsp::float::Float;
+
+/// The length below which transforms stay on a single thread, because
+/// starting threads would cost more than it saves. The `parallel` row of the
+/// `dsp` benchmark measures where this lies.
+pub const THRESHOLD: usize = 1 << 16;
+
+/// The number of threads, or zero before it is first asked for.
+static THREADS: AtomicUsize = AtomicUsize::new(0);
+
+/// The number of threads that large transforms are divided among. This is
+/// determined once, because it may involve reading system files.
+pub fn threads() -> usize {
+    match THREADS.load(Ordering::Relaxed) {
+        0 => {
+            let threads = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+            THREADS.store(threads, Ordering::Relaxed);
+            threads
+        },
+        threads => threads,
+    }
+}
+
+/// Compute the forward discrete Fourier transform of the input on the given
+/// number of threads, splitting it however short it is. Benchmarks use this
+/// to find the length from which threads pay off.
+///
+/// The input length must be at least 2 and have no prime factors other than
+/// 2, 3 and 5, the output slice must be at least as long, and there must be
+/// at least one thread.
+#[doc(hidden)]
+pub fn fdft_split<T: Float>(input: &[Complex<T>], output: &mut [Complex<T>], threads: usize) {
+    let n = input.len();
+    assert!( n >= 2 && is_smooth(n) , "The input length is not supported" );
+    assert!( output.len() >= n      , "The output slice is too small"     );
+    assert!( threads >= 1           , "The number of threads is zero"     );
+    let w = forward_twiddles(n, threads);
+    split(input, output, n, 1, |c| c, &w, threads);
+}
+
+/// Compute the twiddle factors e<sup>&minus;2&pi;ik/n</sup> for all
+/// _k_ &lt; _n_ on the given number of threads.
+pub fn forward_twiddles<T: Float>(n: usize, threads: usize) -> Vec<Complex<T>> {
+    let mut w = vec![Complex(T::ZERO, T::ZERO); n];
+    let nf = n as f64;
+    let chunk = (n + threads - 1) / threads;
+    thread::scope(|scope| {
+        for (c, part) in w.chunks_mut(chunk).enumerate() {
+            scope.spawn(move || {
+                for (k, wk) in (c * chunk ..).zip(part) {
+                    *wk = cis(-2.0*PI*k as f64/nf);
+                }
+            });
+        }
+    });
+    w
+}
+
+/// Compute the transform of _n_ elements of the input, taken at a stride of
+/// _s_, into the first _n_ elements of the output on the given number of
+/// threads. The arguments are those of the single-threaded algorithm.
+pub fn fft<T, F>(i: &[Complex<T>], o: &mut [Complex<T>], n: usize, s: usize, f: F,
+                 w: &[Complex<T>], threads: usize)
+    where T: Float, F: Copy + Send + Sync + Fn(Complex<T>) -> Complex<T> {
+    if threads <= 1 || n < THRESHOLD {
+        unsafe { sequential_fft(i, o, n, s, f, w); }
+        return;
+    }
+    split(i, o, n, s, f, w, threads);
+}
+
+/// Split the transform into its sub-transforms and butterflies, on the given
+/// number of threads.
+fn split<T, F>(i: &[Complex<T>], o: &mut [Complex<T>], n: usize, s: usize, f: F,
+               w: &[Complex<T>], threads: usize)
+    where T: Float, F: Copy + Send + Sync + Fn(Complex<T>) -> Complex<T> {
+    let p = radix(n);
+    let m = n / p;
+    let o = &mut o[..n];
+    thread::scope(|scope| {
+        for (q, part) in o.chunks_mut(m).enumerate() {
+            // Spread the threads over the sub-transforms, giving the first
+            // ones any remainder.
+            let share = threads / p + usize::from(q < threads % p);
+            scope.spawn(move || fft(&i[q*s..], part, m, p*s, f, w, share));
+        }
+    });
+
+    // Butterfly k reads and writes element k of each of the p parts, so the
+    // parts are cut at the same offsets and each thread gets one piece of
+    // every part.
+    let chunk = (m + threads - 1) / threads;
+    let mut pieces: Vec<Vec<&mut [Complex<T>]>> =
+        (0 .. (m + chunk - 1) / chunk).map(|_| Vec::with_capacity(p)).collect();
+    for part in o.chunks_mut(m) {
+        for (piece, slice) in pieces.iter_mut().zip(part.chunks_mut(chunk)) {
+            piece.push(slice);
+        }
+    }
+    let step = w.len() / n;
+    thread::scope(|scope| {
+        for (c, piece) in pieces.into_iter().enumerate() {
+            scope.spawn(move || butterflies(piece, c * chunk, p, m, step, w));
+        }
+    });
+}
+
+/// Perform the butterflies for the offsets from the given one onwards, with
+/// each of the p parts starting at that offset.
+fn butterflies<T: Float>(mut parts: Vec<&mut [Complex<T>]>, offset: usize, p: usize,
+                         m: usize, step: usize, w: &[Complex<T>]) {
+    if p == 2 {
+        let hi = parts.pop().unwrap();
+        let lo = parts.pop().unwrap();
+        radix2(lo, hi, &w[offset*step..], step);
+        return;
+    }
+
+    let mut t = [Complex(T::ZERO, T::ZERO); 5];
+    for j in 0 .. parts[0].len() {
+        let k = offset + j;
+        for (q, tq) in t[..p].iter_mut().enumerate() {
+            *tq = w[q*k*step] * parts[q][j];
+        }
+        for (r, part) in parts.iter_mut().enumerate() {
+            let mut sum = t[0];
+            for (q, &tq) in t[..p].iter().enumerate().skip(1) {
+                sum += w[(q*r % p)*m*step] * tq;
+            }
+            part[j] = sum;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dsp::complex::c128;
+    use dsp::dft::fdft;
+    use dsp::testing::complex_signal as signal;
+
+    #[test]
+    fn test_matches_sequential() {
+        // Lengths that split into two, three and five sub-transforms, on
+        // more threads than sub-transforms.
+        for &n in &[1 << 17, 3 << 16, 177147, 78125] {
+            let input = signal(n);
+            let w: Vec<c128> = (0..n).map(|k| cis(-2.0*PI*k as f64/n as f64)).collect();
+            assert_eq!(forward_twiddles::<f64>(n, 4), w);
+
+            let mut expected = vec![c128(0.0, 0.0); n];
+            let mut actual   = vec![c128(0.0, 0.0); n];
+            unsafe { sequential_fft(&input, &mut expected, n, 1, |c| c, &w); }
+            fft(&input, &mut actual, n, 1, |c| c, &w, 7);
+            assert_eq!(actual, expected, "n = {}", n);
+        }
+    }
+
+    #[test]
+    fn test_fdft_split() {
+        // Lengths below the threshold are split as well.
+        for &n in &[2, 12, 1000, 4096] {
+            let input = signal(n);
+            let mut expected = vec![c128(0.0, 0.0); n];
+            let mut actual   = vec![c128(0.0, 0.0); n];
+            fdft(&input, &mut expected);
+            fdft_split(&input, &mut actual, 3);
+            assert_eq!(actual, expected, "n = {}", n);
+        }
+        assert_eq!(threads(), threads());
+    }
+
+    #[test]
+    #[should_panic(expected = "The number of threads is zero")]
+    fn test_fdft_split_no_threads() {
+        fdft_split(&signal(8), &mut [c128(0.0, 0.0); 8], 0);
+    }
+}
@@ -0,0 +1,286 @@
+//! Precomputed transforms of a fixed length and direction.
+
+use std::f64::consts::PI;
+
+use dsp::complex::Complex;
+use dsp::float::Float;
+use dsp::dft::DigitReversal;
+use dsp::dft::Twiddles;
+use dsp::dft::butterflies;
+use dsp::dft::cis;
+use dsp::dft::is_smooth;
+
+/// The direction of a discrete Fourier transform.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Direction {
+    /// The forward transform, as computed by [fdft].
+    ///
+    /// [fdft]: fn.fdft.html
+    Forward,
+
+    /// The inverse transform, as computed by [idft].
+    ///
+    /// [idft]: fn.idft.html
+    Inverse,
+}
+
+/// A plan computes discrete Fourier transforms of one length in one
+/// direction.
+///
+/// Building a plan computes the twiddle factors and the input permutation
+/// once, so that executing the plan repeatedly on new buffers does not have
+/// to. Executing a plan gives the same results as the [fdft] and [idft]
+/// subroutines.
+///
+/// [fdft]: fn.fdft.html
+/// [idft]: fn.idft.html
+#[derive(Clone, Debug)]
+pub struct Plan<T = f64> {
+    len: usize,
+    direction: Direction,
+    kind: Kind<T>,
+}
+
+#[derive(Clone, Debug)]
+enum Kind<T> {
+    /// The length has no prime factors other than 2, 3 and 5.
+    Smooth {
+        /// For each output position, the input index that the iterative
+        /// algorithm starts from. For powers of two, this is the bit-reversal
+        /// permutation.
+        permutation: Vec<usize>,
+
+        /// The twiddle factors e<sup>&plusmn;2&pi;ik/n</sup>, with the sign
+        /// determined by the direction.
+        twiddles: Vec<Complex<T>>,
+    },
+
+    /// The length is transformed with Bluestein&rsquo;s algorithm.
+    Bluestein {
+        /// The chirp e<sup>&plusmn;&pi;ik&sup2;/n</sup>.
+        chirp: Vec<Complex<T>>,
+
+        /// The forward transform of the convolution kernel, pre-divided by
+        /// the length of the convolution.
+        kernel: Vec<Complex<T>>,
+
+        /// The forward power-of-two plan used for the convolution.
+        inner: Box<Plan<T>>,
+    },
+}
+
+#[allow(clippy::len_without_is_empty)]
+impl<T: Float> Plan<T> {
+    /// Build a plan for transforms of the given length in the given
+    /// direction.
+    ///
+    /// The length must be at least 1.
+    pub fn new(len: usize, direction: Direction) -> Plan<T> {
+        assert!(len >= 1, "The length is zero");
+        let kind =
+            if is_smooth(len) {
+                Kind::Smooth{
+                    permutation: permutation(len),
+                    twiddles:    twiddles(len, direction),
+                }
+            } else {
+                let m     = (2*len - 1).next_power_of_two();
+                let inner = Plan::new(m, Direction::Forward);
+                let chirp = chirp(len, direction);
+
+                let mut b = vec![Complex(T::ZERO, T::ZERO); m];
+                b[0] = chirp[0].conj();
+                for k in 1..len {
+                    b[k  ] = chirp[k].conj();
+                    b[m-k] = chirp[k].conj();
+                }
+                let mut kernel = vec![Complex(T::ZERO, T::ZERO); m];
+                inner.execute(&b, &mut kernel);
+                let mf = T::from_f64(m as f64);
+                for c in &mut kernel {
+                    *c /= mf;
+                }
+
+                Kind::Bluestein{chirp, kernel, inner: Box::new(inner)}
+            };
+        Plan{len, direction, kind}
+    }
+
+    /// The length of the transforms computed by this plan.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// The direction of the transforms computed by this plan.
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// The number of scratch elements that [execute_with_scratch] needs.
+    /// This is zero for lengths with no prime factors other than 2, 3 and 5.
+    ///
+    /// [execute_with_scratch]: #method.execute_with_scratch
+    pub fn scratch_len(&self) -> usize {
+        match self.kind {
+            Kind::Smooth{..}                => 0,
+            Kind::Bluestein{ref kernel, ..} => 2 * kernel.len(),
+        }
+    }
+
+    /// Compute the transform of the input.
+    ///
+    /// The input slice must have exactly as many elements as the length of
+    /// the plan. Otherwise, the same restrictions and liberties apply as
+    /// those to the [fdft] subroutine. Plans for lengths with prime factors
+    /// other than 2, 3 and 5 allocate scratch space on each execution; use
+    /// [execute_with_scratch] to reuse it instead.
+    ///
+    /// [fdft]: fn.fdft.html
+    /// [execute_with_scratch]: #method.execute_with_scratch
+    pub fn execute(&self, input: &[Complex<T>], output: &mut [Complex<T>]) {
+        let mut scratch = vec![Complex(T::ZERO, T::ZERO); self.scratch_len()];
+        self.execute_with_scratch(input, output, &mut scratch);
+    }
+
+    /// Compute the transform of the input, using the given scratch space
+    /// instead of allocating it.
+    ///
+    /// The scratch slice must have at least [scratch_len] elements, and its
+    /// contents on entry and on return are unspecified. Otherwise, the same
+    /// restrictions apply as those to [execute].
+    ///
+    /// [scratch_len]: #method.scratch_len
+    /// [execute]: #method.execute
+    pub fn execute_with_scratch(&self, input: &[Complex<T>], output: &mut [Complex<T>],
+                                scratch: &mut [Complex<T>]) {
+        assert!( input.len() == self.len             , "The input slice has the wrong length" );
+        assert!( output.len() >= self.len            , "The output slice is too small"        );
+        assert!( scratch.len() >= self.scratch_len() , "The scratch slice is too small"       );
+        let output = &mut output[.. self.len];
+
+        match self.kind {
+            Kind::Smooth{ref permutation, ref twiddles} => {
+                for (o, &p) in output.iter_mut().zip(permutation) {
+                    *o = input[p];
+                }
+                butterflies(output, Twiddles::Table(twiddles));
+            },
+            Kind::Bluestein{ref chirp, ref kernel, ref inner} => {
+                let m = kernel.len();
+                let (a, b) = scratch[.. 2*m].split_at_mut(m);
+                for (i, a) in a.iter_mut().enumerate() {
+                    *a = if i < self.len { input[i] * chirp[i] } else { Complex(T::ZERO, T::ZERO) };
+                }
+                inner.execute_with_scratch(a, b, &mut []);
+
+                // The inverse transform is taken by conjugating the input
+                // and output of the forward transform.
+                for ((a, &b), &k) in a.iter_mut().zip(&*b).zip(kernel) {
+                    *a = (b * k).conj();
+                }
+                inner.execute_with_scratch(a, b, &mut []);
+                for ((o, &b), &w) in output.iter_mut().zip(&*b).zip(chirp) {
+                    *o = w * b.conj();
+                }
+            },
+        }
+
+        if self.direction == Direction::Inverse {
+            let nf = T::from_f64(self.len as f64);
+            for o in output {
+                *o /= nf;
+            }
+        }
+    }
+}
+
+fn permutation(n: usize) -> Vec<usize> {
+    let reversal = DigitReversal::new(n);
+    (0..n).map(|position| reversal.apply(position)).collect()
+}
+
+fn sign(direction: Direction) -> f64 {
+    match direction {
+        Direction::Forward => -1.0,
+        Direction::Inverse =>  1.0,
+    }
+}
+
+fn twiddles<T: Float>(n: usize, direction: Direction) -> Vec<Complex<T>> {
+    let nf = n as f64;
+    (0..n)
+        .map(|k| cis(sign(direction)*2.0*PI*k as f64/nf))
+        .collect()
+}
+
+fn chirp<T: Float>(n: usize, direction: Direction) -> Vec<Complex<T>> {
+    let mut chirp = Vec::with_capacity(n);
+    let mut kk = 0;
+    for k in 0..n {
+        let (kkf, nf) = (kk as f64, n as f64);
+        chirp.push(cis(sign(direction)*PI*kkf/nf));
+        kk = (kk + 2*k + 1) % (2*n);
+    }
+    chirp
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dsp::complex::c128;
+    use dsp::dft::fdft;
+    use dsp::dft::idft;
+    use dsp::testing::complex_signal as signal;
+
+    #[test]
+    fn test_plan_matches_subroutines() {
+        for &n in &[1, 2, 8, 12, 45, 64, 97, 100, 1440] {
+            let input = signal(n);
+            for &(direction, dft) in &[(Direction::Forward, fdft as fn(&[c128], &mut [c128])),
+                                       (Direction::Inverse, idft as fn(&[c128], &mut [c128]))] {
+                let plan = Plan::new(n, direction);
+                let mut expected = vec![c128(0.0, 0.0); n];
+                let mut actual   = vec![c128(0.0, 0.0); n];
+                dft(&input, &mut expected);
+                // Execute twice to check that the plan is reusable.
+                plan.execute(&input, &mut actual);
+                plan.execute(&input, &mut actual);
+                for (a, b) in actual.iter().zip(&expected) {
+                    assert_aq!(*a, *b, 1e-9, "n = {}: ", n);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_execute_with_scratch() {
+        for &n in &[12, 97, 100] {
+            let input = signal(n);
+            let plan = Plan::new(n, Direction::Forward);
+            assert_eq!(plan.scratch_len(), if n == 97 { 512 } else { 0 });
+            let mut expected = vec![c128(0.0, 0.0); n];
+            let mut actual   = vec![c128(0.0, 0.0); n];
+            plan.execute(&input, &mut expected);
+            // Leftover contents of the scratch space must not matter.
+            let mut scratch = vec![c128(f64::NAN, 1.0); plan.scratch_len() + 3];
+            plan.execute_with_scratch(&input, &mut actual, &mut scratch);
+            plan.execute_with_scratch(&input, &mut actual, &mut scratch);
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "The scratch slice is too small")]
+    fn test_execute_with_small_scratch() {
+        let plan: Plan = Plan::new(97, Direction::Forward);
+        let input = signal(97);
+        let mut output = vec![c128(0.0, 0.0); 97];
+        let mut scratch = vec![c128(0.0, 0.0); 511];
+        plan.execute_with_scratch(&input, &mut output, &mut scratch);
+    }
+
+    #[test]
+    fn test_permutation_bit_reversal() {
+        assert_eq!(permutation(8), [0, 4, 2, 6, 1, 5, 3, 7]);
+    }
+}
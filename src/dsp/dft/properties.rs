@@ -0,0 +1,131 @@
+//! Properties of the discrete Fourier transform, checked on random inputs of
+//! random lengths.
+//!
+//! Errors are measured relative to the largest element of the expected
+//! result, because the error of a transform is spread over all bins and
+//! small bins have large relative errors. They are reported in units in the
+//! last place (ULPs) of 1, that is as multiples of the machine epsilon. Run
+//! with `--nocapture` to see the largest errors of each property.
+
+use std::f64::consts::PI;
+
+use dsp::complex::c128;
+use dsp::dft::fdft;
+use dsp::dft::idft;
+use dsp::dft::is_smooth;
+use dsp::testing::Random;
+use dsp::testing::naive_dft;
+
+/// The number of random inputs each property is checked on.
+const CASES: usize = 200;
+
+/// The largest length of the random inputs.
+const MAX_LEN: usize = 400;
+
+/// A length in [1, MAX_LEN], with lengths whose only prime factors are 2, 3
+/// and 5, listed in smooth, as likely as the others.
+fn len(random: &mut Random, smooth: &[usize]) -> usize {
+    if random.next() % 2 == 0 {
+        smooth[random.next() as usize % smooth.len()]
+    } else {
+        1 + random.next() as usize % MAX_LEN
+    }
+}
+
+fn transform(input: &[c128]) -> Vec<c128> {
+    let mut output = vec![c128(0.0, 0.0); input.len()];
+    fdft(input, &mut output);
+    output
+}
+
+fn inverse(input: &[c128]) -> Vec<c128> {
+    let mut output = vec![c128(0.0, 0.0); input.len()];
+    idft(input, &mut output);
+    output
+}
+
+/// The largest error of the actual result, relative to the largest element
+/// of the expected result, in ULPs.
+fn ulps(actual: &[c128], expected: &[c128]) -> f64 {
+    let scale = expected.iter().map(|c| c.norm()).fold(0.0, f64::max);
+    let error = actual.iter().zip(expected).map(|(&a, &e)| (a - e).norm()).fold(0.0, f64::max);
+    if scale == 0.0 { error / f64::EPSILON } else { error / scale / f64::EPSILON }
+}
+
+/// The largest error that a transform of length n may have, in ULPs. The
+/// error of the fast algorithms grows with log n, and Bluestein&rsquo;s
+/// algorithm performs three transforms of up to four times the length.
+fn tolerance(n: usize) -> f64 {
+    let log2 = (n as f64).log2().max(1.0);
+    if is_smooth(n) { 4.0 * log2 } else { 8.0 * (log2 + 2.0) }
+}
+
+/// Check the property on CASES random inputs of random lengths, where the
+/// property returns its error in ULPs, and report the largest error.
+fn check<P>(name: &str, seed: u64, property: P) where P: Fn(&mut Random, usize) -> f64 {
+    let mut random = Random::new(seed);
+    let smooth: Vec<usize> = (1 ..= MAX_LEN).filter(|&n| is_smooth(n)).collect();
+    let mut worst = (0.0, 0);
+    for _ in 0 .. CASES {
+        let n = len(&mut random, &smooth);
+        let error = property(&mut random, n);
+        assert!(error <= tolerance(n), "{}: n = {}: {} ULPs", name, n, error);
+        if error > worst.0 {
+            worst = (error, n);
+        }
+    }
+    println!("{}: at most {:.1} ULPs, at n = {}", name, worst.0, worst.1);
+}
+
+#[test]
+fn test_matches_naive_dft() {
+    check("naive", 0x9E3779B97F4A7C15, |random, n| {
+        let x = random.signal(n);
+        ulps(&transform(&x), &naive_dft(&x))
+    });
+}
+
+#[test]
+fn test_roundtrip() {
+    check("roundtrip", 0xD1B54A32D192ED03, |random, n| {
+        let x = random.signal(n);
+        ulps(&inverse(&transform(&x)), &x)
+    });
+}
+
+#[test]
+fn test_parseval() {
+    // The energy of the signal is the energy of its spectrum divided by n.
+    check("parseval", 0x8CB92BA72F3D8DD7, |random, n| {
+        let x = random.signal(n);
+        let time: f64 = x.iter().map(|c| c.norm_sqr()).sum();
+        let frequency: f64 = transform(&x).iter().map(|c| c.norm_sqr()).sum::<f64>() / n as f64;
+        f64::abs(time - frequency) / time / f64::EPSILON
+    });
+}
+
+#[test]
+fn test_linearity() {
+    check("linearity", 0xA0761D6478BD642F, |random, n| {
+        let (x, y) = (random.signal(n), random.signal(n));
+        let (a, b) = (random.complex(), random.complex());
+        let combined: Vec<c128> = x.iter().zip(&y).map(|(&x, &y)| a * x + b * y).collect();
+        let expected: Vec<c128> = transform(&x).iter().zip(transform(&y))
+            .map(|(&fx, fy)| a * fx + b * fy).collect();
+        ulps(&transform(&combined), &expected)
+    });
+}
+
+#[test]
+fn test_time_shift() {
+    // Rotating the signal by s samples multiplies bin k by e^(-2πiks/n).
+    check("shift", 0xE7037ED1A0B428DB, |random, n| {
+        let x = random.signal(n);
+        let s = random.next() as usize % n;
+        let shifted: Vec<c128> = (0..n).map(|j| x[(j + n - s) % n]).collect();
+        let expected: Vec<c128> = transform(&x).iter().enumerate()
+            .map(|(k, &c)| c * c128::from_polar(1.0, -2.0 * PI * ((k * s) % n) as f64 / n as f64))
+            .collect();
+        ulps(&transform(&shifted), &expected)
+    });
+}
@@ -0,0 +1,167 @@
+//! Transforms of real-valued signals.
+//!
+//! The spectrum of a real signal of length _n_ is Hermitian: bin _n_ &minus;
+//! _k_ is the complex conjugate of bin _k_. Only the _n_/2 + 1 bins from
+//! zero up to and including the Nyquist frequency are therefore computed.
+//! For even lengths, the even and odd samples are packed into the real and
+//! imaginary parts of a single complex transform of half the length.
+
+use std::f64::consts::PI;
+
+use dsp::complex::Complex;
+use dsp::dft::cis;
+use dsp::dft::fdft;
+use dsp::dft::idft;
+use dsp::float::Float;
+
+/// Compute the non-redundant half of the forward discrete Fourier transform
+/// of the real input.
+///
+/// When calling this subroutine, you must beware of certain restrictions and
+/// liberties:
+///
+///  - The input slice must have a length _n_ &ge; 1.
+///  - The output slice must have at least _n_/2 + 1 elements.
+///  - The first _n_/2 + 1 elements of the output slice will be overwritten.
+///  - For even lengths, this subroutine allocates scratch space for _n_
+///    complex elements and takes a complex transform of length _n_/2.
+///  - For odd lengths, this subroutine allocates scratch space for 2_n_
+///    complex elements and takes a complex transform of length _n_, so it is
+///    no faster than [fdft] on the widened input.
+///
+/// [fdft]: fn.fdft.html
+pub fn fdft_real<T: Float>(input: &[T], output: &mut [Complex<T>]) {
+    let n = input.len();
+    assert!( n >= 1                , "The input slice is empty"      );
+    assert!( output.len() > n / 2  , "The output slice is too small" );
+
+    if n % 2 == 1 {
+        let widened: Vec<Complex<T>> = input.iter().map(|&x| Complex::from_real(x)).collect();
+        let mut spectrum = vec![Complex(T::ZERO, T::ZERO); n];
+        fdft(&widened, &mut spectrum);
+        output[.. n/2 + 1].copy_from_slice(&spectrum[.. n/2 + 1]);
+        return;
+    }
+
+    let h = n / 2;
+    let packed: Vec<Complex<T>> = input.chunks(2).map(|x| Complex(x[0], x[1])).collect();
+    let mut z = vec![Complex(T::ZERO, T::ZERO); h];
+    fdft(&packed, &mut z);
+
+    let (nf, half) = (n as f64, T::from_f64(0.5));
+    for (k, o) in output[.. h + 1].iter_mut().enumerate() {
+        let zk = z[k % h];
+        let zc = z[(h - k) % h].conj();
+        let even = Complex(half,    T::ZERO) * (zk + zc);
+        let odd  = Complex(T::ZERO, -half  ) * (zk - zc);
+        *o = even + cis(-2.0*PI*k as f64/nf) * odd;
+    }
+}
+
+/// Compute the real inverse discrete Fourier transform of a half spectrum as
+/// returned by [fdft_real].
+///
+/// The length _n_ of the signal is given by the length of the output slice,
+/// which must be at least 1. The input slice must have at least _n_/2 + 1
+/// elements. The imaginary parts of the zero-frequency bin and, for even
+/// lengths, the Nyquist bin are ignored. Scratch space is allocated and the
+/// complex transform taken as for [fdft_real].
+///
+/// [fdft_real]: fn.fdft_real.html
+pub fn idft_real<T: Float>(input: &[Complex<T>], output: &mut [T]) {
+    let n = output.len();
+    assert!( n >= 1               , "The output slice is empty"    );
+    assert!( input.len() > n / 2  , "The input slice is too small" );
+
+    if n % 2 == 1 {
+        let mut spectrum = vec![Complex(T::ZERO, T::ZERO); n];
+        spectrum[0] = Complex::from_real(input[0].real());
+        for k in 1 .. n/2 + 1 {
+            spectrum[k    ] = input[k];
+            spectrum[n - k] = input[k].conj();
+        }
+        let mut signal = vec![Complex(T::ZERO, T::ZERO); n];
+        idft(&spectrum, &mut signal);
+        for (o, s) in output.iter_mut().zip(signal) {
+            *o = s.real();
+        }
+        return;
+    }
+
+    let h = n / 2;
+    let (nf, half) = (n as f64, T::from_f64(0.5));
+    // The zero-frequency and Nyquist bins of a real signal are real.
+    let bin = |k: usize| if k == 0 || k == h { Complex::from_real(input[k].real()) }
+                         else { input[k] };
+    let packed: Vec<Complex<T>> = (0..h).map(|k| {
+        let xk = bin(k);
+        let xc = bin(h - k).conj();
+        let even = Complex(half, T::ZERO) * (xk + xc);
+        let odd  = Complex(half, T::ZERO) * (xk - xc)
+                 * cis(2.0*PI*k as f64/nf);
+        even + Complex::from_imag(T::ONE) * odd
+    }).collect();
+    let mut z = vec![Complex(T::ZERO, T::ZERO); h];
+    idft(&packed, &mut z);
+
+    for (o, z) in output.chunks_mut(2).zip(z) {
+        o[0] = z.real();
+        o[1] = z.imag();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dsp::complex::c128;
+    use dsp::testing::signal;
+
+    #[test]
+    fn test_fdft_real() {
+        for &n in &[1, 2, 3, 7, 8, 100, 1440] {
+            let     input    = signal(n);
+            let     widened  : Vec<c128> = input.iter().map(|&x| c128::from_real(x)).collect();
+            let mut expected = vec![c128(0.0, 0.0); n];
+            let mut actual   = vec![c128(0.0, 0.0); n/2 + 1];
+            fdft(&widened, &mut expected);
+            fdft_real(&input, &mut actual);
+            for (a, b) in actual.iter().zip(&expected) {
+                assert_aq!(*a, *b, 1e-9, "n = {}: ", n);
+            }
+        }
+    }
+
+    #[test]
+    fn test_idft_real() {
+        for &n in &[1, 2, 3, 7, 8, 100, 1440] {
+            let     input    = signal(n);
+            let mut spectrum = vec![c128(0.0, 0.0); n/2 + 1];
+            let mut output   = vec![0.0; n];
+            fdft_real(&input, &mut spectrum);
+            idft_real(&spectrum, &mut output);
+            for (a, b) in output.iter().zip(&input) {
+                assert!(f64::abs(a - b) <= 1e-9, "n = {}: {:?} ≉ {:?}", n, a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_idft_real_ignores_imaginary_parts() {
+        for &n in &[1, 2, 3, 7, 8, 100] {
+            let mut spectrum = vec![c128(0.0, 0.0); n/2 + 1];
+            fdft_real(&signal(n), &mut spectrum);
+            let mut expected = vec![0.0; n];
+            idft_real(&spectrum, &mut expected);
+
+            spectrum[0].1 = 5.0;
+            if n % 2 == 0 {
+                spectrum[n/2].1 = -3.0;
+            }
+            let mut actual = vec![0.0; n];
+            idft_real(&spectrum, &mut actual);
+            for (a, b) in actual.iter().zip(&expected) {
+                assert!(f64::abs(a - b) <= 1e-9, "n = {}: {:?} ≉ {:?}", n, a, b);
+            }
+        }
+    }
+}
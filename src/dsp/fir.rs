@@ -0,0 +1,303 @@
+//! Finite impulse response filters remove unwanted frequency bands from a
+//! signal, for example high-frequency jitter before thresholding.
+//!
+//! Filters are designed with the windowed-sinc method: the impulse response
+//! of the ideal filter is truncated to the requested number of taps and
+//! multiplied by a window to reduce the ripple caused by the truncation.
+//! Frequencies are given in cycles per sample, so that they lie between zero
+//! and the Nyquist frequency of one half.
+
+use std::f64::consts::PI;
+
+use dsp::complex::c128;
+use dsp::convolve::overlap_add;
+use dsp::window::Window;
+
+/// The filters with fewer taps than this are applied directly; longer ones
+/// are applied by multiplying spectra.
+const FFT_THRESHOLD: usize = 64;
+
+/// The frequency response of an ideal filter.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Response {
+    /// Pass the frequencies below the cutoff.
+    LowPass(f64),
+
+    /// Pass the frequencies above the cutoff.
+    HighPass(f64),
+
+    /// Pass the frequencies between the lower and upper cutoffs.
+    BandPass(f64, f64),
+
+    /// Pass the frequencies outside the lower and upper cutoffs.
+    BandStop(f64, f64),
+}
+
+/// Design the coefficients of a linear-phase filter with the given number of
+/// taps that approximates the ideal response.
+///
+/// When calling this subroutine, you must beware of certain restrictions:
+///
+///  - The number of taps must be at least 1.
+///  - High-pass and band-stop filters must have an odd number of taps,
+///    because filters with an even number of taps always block the Nyquist
+///    frequency.
+///  - The cutoffs must lie strictly between zero and one half, and the lower
+///    cutoff of a band must be less than the upper cutoff.
+///
+/// The coefficients are scaled so that the filter has unit gain in the
+/// middle of its pass band: at zero for low-pass and band-stop filters, at
+/// one half for high-pass filters and between the cutoffs for band-pass
+/// filters.
+pub fn design(response: Response, taps: usize, window: Window) -> Vec<f64> {
+    assert!( taps >= 1 , "The number of taps is zero" );
+    let valid = |f: f64| f > 0.0 && f < 0.5;
+    let (ideal, reference) = match response {
+        Response::LowPass(fc) => {
+            assert!( valid(fc) , "The cutoff is out of range" );
+            (low_pass(fc, taps), 0.0)
+        },
+        Response::HighPass(fc) => {
+            assert!( valid(fc)     , "The cutoff is out of range"           );
+            assert!( taps % 2 == 1 , "The number of taps must be odd"       );
+            (spectral_inversion(low_pass(fc, taps)), 0.5)
+        },
+        Response::BandPass(f1, f2) => {
+            assert!( valid(f1) && valid(f2) && f1 < f2 , "The cutoffs are out of range" );
+            let band = low_pass(f2, taps).iter().zip(low_pass(f1, taps))
+                .map(|(h2, h1)| h2 - h1).collect();
+            (band, (f1 + f2) / 2.0)
+        },
+        Response::BandStop(f1, f2) => {
+            assert!( valid(f1) && valid(f2) && f1 < f2 , "The cutoffs are out of range" );
+            assert!( taps % 2 == 1 , "The number of taps must be odd" );
+            let band = low_pass(f2, taps).iter().zip(low_pass(f1, taps))
+                .map(|(h2, h1)| h2 - h1).collect();
+            (spectral_inversion(band), 0.0)
+        },
+    };
+
+    let mut coefficients: Vec<f64> = ideal.iter().zip(window.symmetric(taps))
+        .map(|(h, w)| h * w).collect();
+    let gain = frequency_response(&coefficients, reference).norm();
+    for c in &mut coefficients {
+        *c /= gain;
+    }
+    coefficients
+}
+
+/// Compute the frequency response of the filter with the given coefficients
+/// at the given frequency.
+pub fn frequency_response(coefficients: &[f64], frequency: f64) -> c128 {
+    coefficients.iter().enumerate()
+        .map(|(k, &h)| c128::from_polar(h, -2.0 * PI * frequency * k as f64))
+        .sum()
+}
+
+/// Apply the filter with the given coefficients to the signal, assuming that
+/// the signal is zero before its first sample. The output has the same
+/// length as the signal and is delayed by (taps &minus; 1) / 2 samples.
+pub fn filter(coefficients: &[f64], signal: &[f64]) -> Vec<f64> {
+    let mut fir = Fir::new(coefficients.to_vec());
+    let mut output = vec![0.0; signal.len()];
+    fir.process_block(signal, &mut output);
+    output
+}
+
+/// A filter that is applied to a stream of samples, one or more at a time.
+///
+/// The filter keeps the last samples it processed, so that the output does
+/// not depend on how the stream is split into calls.
+#[derive(Clone, Debug)]
+pub struct Fir {
+    coefficients: Vec<f64>,
+
+    /// The last samples, as a ring buffer that is written at the position.
+    history: Vec<f64>,
+    position: usize,
+}
+
+impl Fir {
+    /// Create a filter with the given coefficients, which must not be empty,
+    /// and a history of zeros.
+    pub fn new(coefficients: Vec<f64>) -> Fir {
+        assert!( !coefficients.is_empty() , "The coefficient slice is empty" );
+        let history = vec![0.0; coefficients.len()];
+        Fir{coefficients, history, position: 0}
+    }
+
+    /// The coefficients of the filter.
+    pub fn coefficients(&self) -> &[f64] {
+        &self.coefficients
+    }
+
+    /// Forget the processed samples.
+    pub fn reset(&mut self) {
+        for h in &mut self.history {
+            *h = 0.0;
+        }
+        self.position = 0;
+    }
+
+    /// Process the next sample and return the next output.
+    pub fn process(&mut self, sample: f64) -> f64 {
+        let taps = self.coefficients.len();
+        self.history[self.position] = sample;
+        let (older, newer) = self.history.split_at(self.position + 1);
+        let output = newer.iter().chain(older).rev().zip(&self.coefficients)
+            .map(|(x, h)| x * h).sum();
+        self.position = (self.position + 1) % taps;
+        output
+    }
+
+    /// Process the next samples and write as many outputs.
+    ///
+    /// Long filters applied to long blocks are computed by multiplying
+    /// spectra, which gives the same outputs up to rounding.
+    pub fn process_block(&mut self, input: &[f64], output: &mut [f64]) {
+        assert!( output.len() >= input.len() , "The output slice is too small" );
+        let taps = self.coefficients.len();
+        if taps < FFT_THRESHOLD || input.len() < taps {
+            for (o, &x) in output.iter_mut().zip(input) {
+                *o = self.process(x);
+            }
+            return;
+        }
+
+        // Prepend the last taps - 1 samples, oldest first, so that the
+        // convolution continues where the previous call left off.
+        let mut extended = Vec::with_capacity(taps - 1 + input.len());
+        for k in 1 .. taps {
+            extended.push(self.history[(self.position + k) % taps]);
+        }
+        extended.extend_from_slice(input);
+
+        let convolved = overlap_add(&extended, &self.coefficients);
+        output[.. input.len()].copy_from_slice(&convolved[taps - 1 .. taps - 1 + input.len()]);
+
+        // The history holds the last taps samples and is written next at the
+        // position, which is therefore that of the oldest sample.
+        self.history.copy_from_slice(&extended[extended.len() - taps ..]);
+        self.position = 0;
+    }
+}
+
+/// The ideal low-pass impulse response, centred on the middle tap.
+fn low_pass(cutoff: f64, taps: usize) -> Vec<f64> {
+    let middle = (taps - 1) as f64 / 2.0;
+    (0..taps).map(|k| {
+        let t = k as f64 - middle;
+        if t == 0.0 {
+            2.0 * cutoff
+        } else {
+            f64::sin(2.0 * PI * cutoff * t) / (PI * t)
+        }
+    }).collect()
+}
+
+/// Turn the impulse response of a filter into that of its complement, by
+/// subtracting it from a unit impulse at the middle tap.
+fn spectral_inversion(mut coefficients: Vec<f64>) -> Vec<f64> {
+    for c in &mut coefficients {
+        *c = -*c;
+    }
+    let middle = coefficients.len() / 2;
+    coefficients[middle] += 1.0;
+    coefficients
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dsp::testing::signal;
+    use dsp::testing::sinusoid;
+
+    fn gain(coefficients: &[f64], frequency: f64) -> f64 {
+        frequency_response(coefficients, frequency).norm()
+    }
+
+    #[test]
+    fn test_design() {
+        let lp = design(Response::LowPass(0.1), 101, Window::Hamming);
+        assert!(f64::abs(gain(&lp, 0.0) - 1.0) <= 1e-9);
+        assert!(f64::abs(gain(&lp, 0.05) - 1.0) <= 0.01);
+        assert!(gain(&lp, 0.2) <= 0.01);
+
+        let hp = design(Response::HighPass(0.1), 101, Window::Hamming);
+        assert!(f64::abs(gain(&hp, 0.5) - 1.0) <= 1e-9);
+        assert!(gain(&hp, 0.0) <= 0.01);
+        assert!(f64::abs(gain(&hp, 0.3) - 1.0) <= 0.01);
+
+        let bp = design(Response::BandPass(0.1, 0.3), 101, Window::Blackman);
+        assert!(f64::abs(gain(&bp, 0.2) - 1.0) <= 1e-9);
+        assert!(gain(&bp, 0.0) <= 0.01);
+        assert!(gain(&bp, 0.45) <= 0.01);
+
+        let bs = design(Response::BandStop(0.1, 0.3), 101, Window::Blackman);
+        assert!(f64::abs(gain(&bs, 0.0) - 1.0) <= 1e-9);
+        assert!(gain(&bs, 0.2) <= 0.01);
+        assert!(f64::abs(gain(&bs, 0.5) - 1.0) <= 0.01);
+    }
+
+    #[test]
+    fn test_design_linear_phase() {
+        let lp = design(Response::LowPass(0.2), 30, Window::Kaiser(6.0));
+        for k in 0..30 {
+            assert!(f64::abs(lp[k] - lp[29 - k]) <= 1e-15);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "The number of taps must be odd")]
+    fn test_design_even_high_pass() {
+        design(Response::HighPass(0.1), 10, Window::Hann);
+    }
+
+    #[test]
+    fn test_process_matches_convolution() {
+        let coefficients = design(Response::LowPass(0.05), 31, Window::Hann);
+        let input = signal(200);
+        let mut fir = Fir::new(coefficients.clone());
+        for (j, &x) in input.iter().enumerate() {
+            let expected: f64 = coefficients.iter().enumerate()
+                .filter(|&(k, _)| k <= j)
+                .map(|(k, h)| h * input[j - k]).sum();
+            assert!(f64::abs(fir.process(x) - expected) <= 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_process_block_fft_path() {
+        // Long enough to take the spectral path, split at odd boundaries.
+        let coefficients = design(Response::LowPass(0.05), 129, Window::Blackman);
+        let input = signal(3000);
+        let mut expected = vec![0.0; input.len()];
+        let mut direct = Fir::new(coefficients.clone());
+        for (o, &x) in expected.iter_mut().zip(&input) {
+            *o = direct.process(x);
+        }
+
+        let mut fir = Fir::new(coefficients.clone());
+        let mut actual = vec![0.0; input.len()];
+        for &(from, to) in &[(0, 1000), (1000, 1100), (1100, 1101), (1101, 3000)] {
+            fir.process_block(&input[from .. to], &mut actual[from .. to]);
+        }
+        for (a, b) in actual.iter().zip(&expected) {
+            assert!(f64::abs(a - b) <= 1e-9, "{} ≉ {}", a, b);
+        }
+        assert_eq!(filter(&coefficients, &input).len(), input.len());
+    }
+
+    #[test]
+    fn test_strips_jitter() {
+        let coefficients = design(Response::LowPass(0.05), 101, Window::Hamming);
+        let slow = sinusoid(2000, 1.0, 0.008, 1.0);
+        let input: Vec<f64> = slow.iter().zip(sinusoid(2000, 1.0, 0.46, 0.3)).map(|(a, b)| a + b).collect();
+        let output = filter(&coefficients, &input);
+        // After the delay of 50 samples, only the slow sinusoid is left.
+        for (j, &y) in output.iter().enumerate().skip(200) {
+            let expected = slow[j - 50];
+            assert!(f64::abs(y - expected) <= 0.01, "{}", j);
+        }
+    }
+}
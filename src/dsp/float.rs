@@ -0,0 +1,154 @@
+//! Floating-point types that digital signal processing subroutines are
+//! generic over.
+
+use std::fmt::Debug;
+use std::fmt::Display;
+use std::ops::Add;
+use std::ops::Div;
+use std::ops::Mul;
+use std::ops::Neg;
+use std::ops::Sub;
+
+/// A floating-point type, implemented for `f32` and `f64`.
+///
+/// Subroutines that compute angles or other constants do so in `f64` and
+/// convert the result with [from_f64], so that the `f32` variants lose as
+/// little accuracy as possible.
+///
+/// [from_f64]: #tymethod.from_f64
+pub trait Float: Copy + Debug + Display + PartialEq + PartialOrd + Send + Sync
+               + Add<Output = Self> + Sub<Output = Self>
+               + Mul<Output = Self> + Div<Output = Self>
+               + Neg<Output = Self> {
+    /// Zero.
+    const ZERO: Self;
+
+    /// One.
+    const ONE: Self;
+
+    /// Positive infinity.
+    const INFINITY: Self;
+
+    /// The largest finite value.
+    const MAX: Self;
+
+    /// The nearest value to the given `f64`.
+    fn from_f64(value: f64) -> Self;
+
+    /// The value widened to `f64`.
+    fn to_f64(self) -> f64;
+
+    /// The sine and cosine of the value.
+    fn sin_cos(self) -> (Self, Self);
+
+    /// The absolute value.
+    fn abs(self) -> Self;
+
+    /// The square root of the value.
+    fn sqrt(self) -> Self;
+
+    /// The exponential function of the value.
+    fn exp(self) -> Self;
+
+    /// The natural logarithm of the value.
+    fn ln(self) -> Self;
+
+    /// The value raised to the given power.
+    fn powf(self, e: Self) -> Self;
+
+    /// The four-quadrant arctangent of the value divided by the argument.
+    fn atan2(self, x: Self) -> Self;
+
+    /// The length of the hypotenuse of the right triangle with the value and
+    /// the argument as legs, without intermediate overflow or underflow.
+    fn hypot(self, y: Self) -> Self;
+
+    /// The value with the sign of the argument.
+    fn copysign(self, sign: Self) -> Self;
+
+    /// Whether the value is not a number.
+    fn is_nan(self) -> bool;
+
+    /// Whether the value is positive or negative infinity.
+    fn is_infinite(self) -> bool;
+}
+
+macro_rules! impl_float {
+    ($t:ident) => {
+        impl Float for $t {
+            const ZERO: $t = 0.0;
+            const ONE:  $t = 1.0;
+
+            const INFINITY: $t = $t::INFINITY;
+            const MAX:      $t = $t::MAX;
+
+            #[inline(always)]
+            fn from_f64(value: f64) -> $t {
+                value as $t
+            }
+
+            #[inline(always)]
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            #[inline(always)]
+            fn sin_cos(self) -> ($t, $t) {
+                $t::sin_cos(self)
+            }
+
+            #[inline(always)]
+            fn abs(self) -> $t {
+                $t::abs(self)
+            }
+
+            #[inline(always)]
+            fn sqrt(self) -> $t {
+                $t::sqrt(self)
+            }
+
+            #[inline(always)]
+            fn exp(self) -> $t {
+                $t::exp(self)
+            }
+
+            #[inline(always)]
+            fn ln(self) -> $t {
+                $t::ln(self)
+            }
+
+            #[inline(always)]
+            fn powf(self, e: $t) -> $t {
+                $t::powf(self, e)
+            }
+
+            #[inline(always)]
+            fn atan2(self, x: $t) -> $t {
+                $t::atan2(self, x)
+            }
+
+            #[inline(always)]
+            fn hypot(self, y: $t) -> $t {
+                $t::hypot(self, y)
+            }
+
+            #[inline(always)]
+            fn copysign(self, sign: $t) -> $t {
+                $t::copysign(self, sign)
+            }
+
+            #[inline(always)]
+            fn is_nan(self) -> bool {
+                $t::is_nan(self)
+            }
+
+            #[inline(always)]
+            fn is_infinite(self) -> bool {
+                $t::is_infinite(self)
+            }
+        }
+    };
+}
+
+impl_float!(f32);
+impl_float!(f64);
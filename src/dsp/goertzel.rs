@@ -0,0 +1,143 @@
+//! The Goertzel algorithm computes the discrete Fourier transform of a real
+//! signal at a single frequency, such as that of a known polling artifact.
+//!
+//! Each sample costs one multiplication and two additions, and only two state
+//! variables are kept, so this is cheaper than a full transform when only a
+//! few frequencies are of interest. The frequency need not be a multiple of
+//! one over the length of the signal.
+
+use std::f64::consts::PI;
+
+use dsp::complex::c128;
+
+/// Compute the discrete Fourier transform of the signal at the given
+/// frequency, in cycles per sample.
+///
+/// For the frequency _k_/_n_, where _n_ is the length of the signal, this is
+/// bin _k_ of the output of [fdft].
+///
+/// [fdft]: ../dft/fn.fdft.html
+pub fn goertzel(signal: &[f64], frequency: f64) -> c128 {
+    let mut goertzel = Goertzel::new(frequency);
+    goertzel.process_block(signal);
+    goertzel.result()
+}
+
+/// A Goertzel filter that is fed a stream of samples, one or more at a time,
+/// and can report the transform of the samples so far at any point.
+#[derive(Clone, Debug)]
+pub struct Goertzel {
+    omega: f64,
+    coefficient: f64,
+    s1: f64,
+    s2: f64,
+    count: usize,
+}
+
+impl Goertzel {
+    /// Create a filter for the given frequency, in cycles per sample.
+    pub fn new(frequency: f64) -> Goertzel {
+        let omega = 2.0 * PI * frequency;
+        Goertzel{omega, coefficient: 2.0 * f64::cos(omega), s1: 0.0, s2: 0.0, count: 0}
+    }
+
+    /// Create a filter for bin _k_ of the transform of a signal of length
+    /// _n_, which must not be zero.
+    pub fn bin(k: usize, n: usize) -> Goertzel {
+        assert!( n >= 1 , "The length is zero" );
+        Goertzel::new(k as f64 / n as f64)
+    }
+
+    /// Forget the processed samples.
+    pub fn reset(&mut self) {
+        self.s1 = 0.0;
+        self.s2 = 0.0;
+        self.count = 0;
+    }
+
+    /// Process the next sample.
+    #[inline]
+    pub fn process(&mut self, sample: f64) {
+        let s0 = sample + self.coefficient * self.s1 - self.s2;
+        self.s2 = self.s1;
+        self.s1 = s0;
+        self.count += 1;
+    }
+
+    /// Process the next samples.
+    pub fn process_block(&mut self, samples: &[f64]) {
+        for &x in samples {
+            self.process(x);
+        }
+    }
+
+    /// The discrete Fourier transform at the frequency of the samples
+    /// processed since the filter was created or reset.
+    pub fn result(&self) -> c128 {
+        if self.count == 0 {
+            return c128(0.0, 0.0);
+        }
+        // The filter output is the transform delayed by count - 1 samples.
+        let y = c128::from_real(self.s1) - c128::from_polar(self.s2, -self.omega);
+        y * c128::from_polar(1.0, -self.omega * (self.count - 1) as f64)
+    }
+
+    /// The squared magnitude of the [result], which is computed without
+    /// trigonometric functions.
+    ///
+    /// [result]: #method.result
+    pub fn power(&self) -> f64 {
+        self.s1 * self.s1 + self.s2 * self.s2 - self.coefficient * self.s1 * self.s2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dsp::dft::fdft;
+    use dsp::testing::signal;
+
+    #[test]
+    fn test_matches_fdft() {
+        for &n in &[1, 8, 60, 97, 300] {
+            let     input    = signal(n);
+            let     widened  : Vec<c128> = input.iter().map(|&x| c128::from_real(x)).collect();
+            let mut spectrum = vec![c128(0.0, 0.0); n];
+            fdft(&widened, &mut spectrum);
+            for (k, &expected) in spectrum.iter().enumerate() {
+                let mut g = Goertzel::bin(k, n);
+                g.process_block(&input);
+                let actual = g.result();
+                let tolerance = 1e-9 * n as f64;
+                assert!((actual - expected).norm() <= tolerance, "n = {}, k = {}: {:?} ≉ {:?}", n, k, actual, expected);
+                assert!(f64::abs(g.power() - expected.norm_sqr()) <= tolerance * expected.norm(), "n = {}, k = {}", n, k);
+            }
+        }
+    }
+
+    #[test]
+    fn test_fractional_frequency() {
+        let input = signal(100);
+        let frequency = 0.123;
+        let expected: c128 = input.iter().enumerate()
+            .map(|(j, &x)| c128::from_polar(x, -2.0 * PI * frequency * j as f64))
+            .sum();
+        let actual = goertzel(&input, frequency);
+        assert!((actual - expected).norm() <= 1e-9, "{:?} ≉ {:?}", actual, expected);
+    }
+
+    #[test]
+    fn test_streaming() {
+        // A 5-minute polling artifact in a series sampled every minute.
+        let input = signal(60);
+        let mut g = Goertzel::new(0.2);
+        for &x in &input[.. 25] {
+            g.process(x);
+        }
+        g.process_block(&input[25 ..]);
+        assert_eq!(g.result(), goertzel(&input, 0.2));
+        assert!(g.result().norm() > 2.0 * goertzel(&input, 0.1).norm());
+        g.reset();
+        assert_eq!(g.result(), c128(0.0, 0.0));
+    }
+}
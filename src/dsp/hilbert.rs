@@ -0,0 +1,131 @@
+//! The Hilbert transform turns a real signal into an analytic signal, whose
+//! magnitude is the envelope of the signal, such as the growing amplitude of
+//! a traffic oscillation.
+//!
+//! The analytic signal is computed in the frequency domain: the negative
+//! frequencies are removed and the positive ones are doubled, so that the
+//! real part of the result is the original signal and the imaginary part is
+//! its Hilbert transform.
+
+use std::f64::consts::PI;
+
+use dsp::complex::c128;
+use dsp::dft::fdft;
+use dsp::dft::idft;
+
+/// The instantaneous properties of a signal, derived from its analytic
+/// signal. Each has one element per sample.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Instantaneous {
+    /// The envelope of the signal, in units of the signal.
+    pub amplitude: Vec<f64>,
+
+    /// The unwrapped phase of the signal, in radians.
+    pub phase: Vec<f64>,
+
+    /// The rate of change of the phase, in cycles per sample.
+    pub frequency: Vec<f64>,
+}
+
+/// Compute the analytic signal of the signal, which must not be empty.
+///
+/// The signal is treated as periodic, so the first and last samples of the
+/// result are distorted if the signal does not wrap around smoothly.
+/// Removing the mean or a trend first reduces the distortion.
+pub fn analytic(signal: &[f64]) -> Vec<c128> {
+    assert!( !signal.is_empty() , "The input slice is empty" );
+    let n = signal.len();
+    let widened: Vec<c128> = signal.iter().map(|&x| c128::from_real(x)).collect();
+    let mut spectrum = vec![c128(0.0, 0.0); n];
+    fdft(&widened, &mut spectrum);
+
+    // Bin zero and, for even lengths, the Nyquist bin have no twin and are
+    // kept as they are.
+    for (k, c) in spectrum.iter_mut().enumerate().skip(1) {
+        if 2 * k < n {
+            *c *= 2.0;
+        } else if 2 * k > n {
+            *c = c128(0.0, 0.0);
+        }
+    }
+
+    let mut output = vec![c128(0.0, 0.0); n];
+    idft(&spectrum, &mut output);
+    output
+}
+
+/// Compute the instantaneous amplitude, phase and frequency of the signal,
+/// which must not be empty.
+///
+/// The frequency is the central difference of the phase, and a one-sided
+/// difference at the first and last samples. A signal of one sample has a
+/// frequency of zero.
+pub fn instantaneous(signal: &[f64]) -> Instantaneous {
+    let z = analytic(signal);
+    let amplitude = z.iter().map(|c| c.norm()).collect();
+
+    let mut phase: Vec<f64> = Vec::with_capacity(z.len());
+    for c in &z {
+        let wrapped = c.arg();
+        let unwrapped = match phase.last() {
+            Some(&previous) => wrapped + 2.0 * PI * f64::round((previous - wrapped) / (2.0 * PI)),
+            None            => wrapped,
+        };
+        phase.push(unwrapped);
+    }
+
+    let n = phase.len();
+    let frequency = (0..n).map(|j| {
+        let (from, to) = (j.saturating_sub(1), usize::min(j + 1, n - 1));
+        if from == to {
+            return 0.0;
+        }
+        (phase[to] - phase[from]) / (2.0 * PI * (to - from) as f64)
+    }).collect();
+
+    Instantaneous{amplitude, phase, frequency}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_analytic_cosine() {
+        // The analytic signal of a cosine is a complex exponential.
+        for &n in &[64, 75] {
+            let input: Vec<f64> = (0..n).map(|j| f64::cos(2.0 * PI * 5.0 * j as f64 / n as f64)).collect();
+            let z = analytic(&input);
+            for (j, c) in z.iter().enumerate() {
+                let expected = c128::from_polar(1.0, 2.0 * PI * 5.0 * j as f64 / n as f64);
+                assert!((*c - expected).norm() <= 1e-12, "{:?} ≉ {:?}", c, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_growing_envelope() {
+        // An oscillation of 0.1 cycles per sample whose amplitude grows
+        // slowly from 1 to 3.
+        let n = 1000;
+        let envelope = |j: usize| 1.0 + 2.0 * j as f64 / n as f64;
+        let input: Vec<f64> = (0..n).map(|j| envelope(j) * f64::sin(0.2 * PI * j as f64)).collect();
+        let result = instantaneous(&input);
+        assert_eq!(result.amplitude.len(), n);
+        assert_eq!(result.phase.len(), n);
+        assert_eq!(result.frequency.len(), n);
+        // Away from the edges, where the signal wraps around.
+        for j in 100 .. 900 {
+            assert!(f64::abs(result.amplitude[j] - envelope(j)) <= 0.02, "{}: {}", j, result.amplitude[j]);
+            assert!(f64::abs(result.frequency[j] - 0.1) <= 1e-3, "{}: {}", j, result.frequency[j]);
+        }
+        assert!(result.phase[n - 1] - result.phase[0] > 2.0 * PI * 90.0);
+    }
+
+    #[test]
+    fn test_single_sample() {
+        let result = instantaneous(&[-2.0]);
+        assert_eq!(result.amplitude, [2.0]);
+        assert_eq!(result.frequency, [0.0]);
+    }
+}
@@ -0,0 +1,261 @@
+//! Infinite impulse response filters smooth a signal at a fraction of the
+//! cost of a finite impulse response filter with a similar response, which
+//! makes them suited to filtering thousands of metrics at once.
+//!
+//! Filters are cascades of second-order sections, or _biquads_, which are
+//! numerically better behaved than a single high-order section. The biquads
+//! are designed with the formulas from Robert Bristow-Johnson&rsquo;s _[Audio
+//! EQ Cookbook][cookbook]_. Frequencies are given in cycles per sample, so
+//! that they lie between zero and the Nyquist frequency of one half.
+//!
+//! [cookbook]: https://www.w3.org/TR/audio-eq-cookbook/
+
+use std::f64::consts::PI;
+
+use dsp::complex::c128;
+
+/// The coefficients of a second-order section with the transfer function
+/// (_b_<sub>0</sub> + _b_<sub>1</sub>_z_<sup>&minus;1</sup> +
+/// _b_<sub>2</sub>_z_<sup>&minus;2</sup>) / (1 + _a_<sub>1</sub>_z_<sup>&minus;1</sup>
+/// + _a_<sub>2</sub>_z_<sup>&minus;2</sup>).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Biquad {
+    pub b0: f64,
+    pub b1: f64,
+    pub b2: f64,
+    pub a1: f64,
+    pub a2: f64,
+}
+
+impl Biquad {
+    /// A low-pass section with the given cutoff and quality factor. A quality
+    /// factor of 1/&radic;2 gives a maximally flat pass band.
+    pub fn low_pass(frequency: f64, q: f64) -> Biquad {
+        let (cos, alpha) = prewarp(frequency, q);
+        Biquad::normalize([(1.0 - cos) / 2.0, 1.0 - cos, (1.0 - cos) / 2.0],
+                          [1.0 + alpha, -2.0 * cos, 1.0 - alpha])
+    }
+
+    /// A high-pass section with the given cutoff and quality factor.
+    pub fn high_pass(frequency: f64, q: f64) -> Biquad {
+        let (cos, alpha) = prewarp(frequency, q);
+        Biquad::normalize([(1.0 + cos) / 2.0, -(1.0 + cos), (1.0 + cos) / 2.0],
+                          [1.0 + alpha, -2.0 * cos, 1.0 - alpha])
+    }
+
+    /// A band-pass section with unit gain at the given centre frequency. The
+    /// quality factor is the centre frequency divided by the bandwidth.
+    pub fn band_pass(frequency: f64, q: f64) -> Biquad {
+        let (cos, alpha) = prewarp(frequency, q);
+        Biquad::normalize([alpha, 0.0, -alpha],
+                          [1.0 + alpha, -2.0 * cos, 1.0 - alpha])
+    }
+
+    /// A notch section that blocks the given centre frequency. The quality
+    /// factor is the centre frequency divided by the bandwidth.
+    pub fn notch(frequency: f64, q: f64) -> Biquad {
+        let (cos, alpha) = prewarp(frequency, q);
+        Biquad::normalize([1.0, -2.0 * cos, 1.0],
+                          [1.0 + alpha, -2.0 * cos, 1.0 - alpha])
+    }
+
+    /// Compute the frequency response of the section at the given frequency.
+    pub fn frequency_response(&self, frequency: f64) -> c128 {
+        let z1 = c128::from_polar(1.0, -2.0 * PI * frequency);
+        let z2 = z1 * z1;
+        let one = c128::from_real(1.0);
+        (one * self.b0 + z1 * self.b1 + z2 * self.b2)
+            / (one + z1 * self.a1 + z2 * self.a2)
+    }
+
+    fn normalize(b: [f64; 3], a: [f64; 3]) -> Biquad {
+        Biquad{
+            b0: b[0] / a[0],
+            b1: b[1] / a[0],
+            b2: b[2] / a[0],
+            a1: a[1] / a[0],
+            a2: a[2] / a[0],
+        }
+    }
+}
+
+/// The cosine of the angular frequency and the bandwidth parameter &alpha;
+/// of the cookbook formulas.
+fn prewarp(frequency: f64, q: f64) -> (f64, f64) {
+    assert!( frequency > 0.0 && frequency < 0.5 , "The frequency is out of range"   );
+    assert!( q > 0.0                            , "The quality factor is not positive" );
+    let (sin, cos) = f64::sin_cos(2.0 * PI * frequency);
+    (cos, sin / (2.0 * q))
+}
+
+/// A cascade of second-order sections that is applied to a stream of
+/// samples, one or more at a time.
+///
+/// Each section is computed in the transposed direct form II, which keeps
+/// two state variables per section between calls.
+#[derive(Clone, Debug)]
+pub struct Iir {
+    sections: Vec<Biquad>,
+    state: Vec<[f64; 2]>,
+}
+
+impl Iir {
+    /// Create a filter that applies the given sections in order, with a
+    /// state of zeros.
+    pub fn new(sections: Vec<Biquad>) -> Iir {
+        let state = vec![[0.0; 2]; sections.len()];
+        Iir{sections, state}
+    }
+
+    /// Create a Butterworth low-pass filter of the given order, which must be
+    /// at least 1, and cutoff, at which the gain is 1/&radic;2.
+    pub fn butterworth_low_pass(order: usize, cutoff: f64) -> Iir {
+        Iir::new(butterworth(order, cutoff, Biquad::low_pass, first_order_low_pass))
+    }
+
+    /// Create a Butterworth high-pass filter of the given order, which must
+    /// be at least 1, and cutoff, at which the gain is 1/&radic;2.
+    pub fn butterworth_high_pass(order: usize, cutoff: f64) -> Iir {
+        Iir::new(butterworth(order, cutoff, Biquad::high_pass, first_order_high_pass))
+    }
+
+    /// The sections of the filter.
+    pub fn sections(&self) -> &[Biquad] {
+        &self.sections
+    }
+
+    /// Forget the processed samples.
+    pub fn reset(&mut self) {
+        for s in &mut self.state {
+            *s = [0.0; 2];
+        }
+    }
+
+    /// Process the next sample and return the next output.
+    #[inline]
+    pub fn process(&mut self, sample: f64) -> f64 {
+        let mut x = sample;
+        for (s, state) in self.sections.iter().zip(&mut self.state) {
+            let y = s.b0 * x + state[0];
+            state[0] = s.b1 * x - s.a1 * y + state[1];
+            state[1] = s.b2 * x - s.a2 * y;
+            x = y;
+        }
+        x
+    }
+
+    /// Process the next samples and write as many outputs.
+    pub fn process_block(&mut self, input: &[f64], output: &mut [f64]) {
+        assert!( output.len() >= input.len() , "The output slice is too small" );
+        for (o, &x) in output.iter_mut().zip(input) {
+            *o = self.process(x);
+        }
+    }
+
+    /// Compute the frequency response of the filter at the given frequency.
+    pub fn frequency_response(&self, frequency: f64) -> c128 {
+        self.sections.iter().map(|s| s.frequency_response(frequency)).product()
+    }
+}
+
+/// The sections of a Butterworth filter. The poles are spread evenly over the
+/// left half of the unit circle at the angles &theta; = &pi;(2_k_ + 1)/(2_n_)
+/// from the imaginary axis. Each conjugate pair becomes a section with the
+/// quality factor 1/(2 sin &theta;), and the real pole of an odd order
+/// becomes a first-order section.
+fn butterworth<S, F>(order: usize, cutoff: f64, section: S, first_order: F) -> Vec<Biquad>
+    where S: Fn(f64, f64) -> Biquad, F: Fn(f64) -> Biquad {
+    assert!( order >= 1 , "The order is zero" );
+    let mut sections: Vec<Biquad> = (0 .. order / 2).map(|k| {
+        let theta = PI * (2 * k + 1) as f64 / (2 * order) as f64;
+        section(cutoff, 1.0 / (2.0 * f64::sin(theta)))
+    }).collect();
+    if order % 2 == 1 {
+        sections.push(first_order(cutoff));
+    }
+    sections
+}
+
+fn first_order_low_pass(cutoff: f64) -> Biquad {
+    assert!( cutoff > 0.0 && cutoff < 0.5 , "The frequency is out of range" );
+    let k = f64::tan(PI * cutoff);
+    Biquad{b0: k / (k + 1.0), b1: k / (k + 1.0), b2: 0.0, a1: (k - 1.0) / (k + 1.0), a2: 0.0}
+}
+
+fn first_order_high_pass(cutoff: f64) -> Biquad {
+    assert!( cutoff > 0.0 && cutoff < 0.5 , "The frequency is out of range" );
+    let k = f64::tan(PI * cutoff);
+    Biquad{b0: 1.0 / (k + 1.0), b1: -1.0 / (k + 1.0), b2: 0.0, a1: (k - 1.0) / (k + 1.0), a2: 0.0}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::FRAC_1_SQRT_2;
+
+    fn gain<F>(response: F, frequency: f64) -> f64 where F: Fn(f64) -> c128 {
+        response(frequency).norm()
+    }
+
+    #[test]
+    fn test_biquads() {
+        let lp = Biquad::low_pass(0.1, FRAC_1_SQRT_2);
+        assert!(f64::abs(gain(|f| lp.frequency_response(f), 0.0) - 1.0) <= 1e-12);
+        assert!(f64::abs(gain(|f| lp.frequency_response(f), 0.1) - FRAC_1_SQRT_2) <= 1e-12);
+        assert!(gain(|f| lp.frequency_response(f), 0.5) <= 1e-12);
+
+        let hp = Biquad::high_pass(0.1, FRAC_1_SQRT_2);
+        assert!(gain(|f| hp.frequency_response(f), 0.0) <= 1e-12);
+        assert!(f64::abs(gain(|f| hp.frequency_response(f), 0.5) - 1.0) <= 1e-12);
+
+        let bp = Biquad::band_pass(0.2, 2.0);
+        assert!(f64::abs(gain(|f| bp.frequency_response(f), 0.2) - 1.0) <= 1e-12);
+        assert!(gain(|f| bp.frequency_response(f), 0.0) <= 1e-12);
+
+        let notch = Biquad::notch(0.2, 2.0);
+        assert!(gain(|f| notch.frequency_response(f), 0.2) <= 1e-12);
+        assert!(f64::abs(gain(|f| notch.frequency_response(f), 0.0) - 1.0) <= 1e-12);
+    }
+
+    #[test]
+    fn test_butterworth() {
+        for order in 1 .. 8 {
+            let lp = Iir::butterworth_low_pass(order, 0.05);
+            assert_eq!(lp.sections().len(), (order + 1) / 2);
+            assert!(f64::abs(gain(|f| lp.frequency_response(f), 0.0) - 1.0) <= 1e-9);
+            assert!(f64::abs(gain(|f| lp.frequency_response(f), 0.05) - FRAC_1_SQRT_2) <= 1e-9);
+            // The pass band is monotonic.
+            let mut previous = 1.0 + 1e-12;
+            for k in 0 .. 50 {
+                let g = gain(|f| lp.frequency_response(f), k as f64 / 100.0);
+                assert!(g <= previous, "order {}", order);
+                previous = g;
+            }
+
+            let hp = Iir::butterworth_high_pass(order, 0.05);
+            assert!(f64::abs(gain(|f| hp.frequency_response(f), 0.5) - 1.0) <= 1e-9);
+            assert!(f64::abs(gain(|f| hp.frequency_response(f), 0.05) - FRAC_1_SQRT_2) <= 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_process_keeps_state() {
+        let mut whole = Iir::butterworth_low_pass(4, 0.02);
+        let mut split = whole.clone();
+        let input: Vec<f64> = (0 .. 1000).map(|j| if j < 10 { 0.0 } else { 1.0 }).collect();
+        let mut expected = vec![0.0; 1000];
+        whole.process_block(&input, &mut expected);
+
+        let mut actual = vec![0.0; 1000];
+        for (o, &x) in actual[.. 400].iter_mut().zip(&input[.. 400]) {
+            *o = split.process(x);
+        }
+        split.process_block(&input[400 ..], &mut actual[400 ..]);
+        assert_eq!(actual, expected);
+
+        // The step response settles at the pass-band gain.
+        assert!(f64::abs(expected[999] - 1.0) <= 1e-6);
+        split.reset();
+        assert_eq!(split.process(0.0), 0.0);
+    }
+}
@@ -6,5 +6,52 @@
 //!
 //! [dspguide]: https://dspguide.com/
 
+#[cfg(test)]
+#[macro_use]
+mod testing;
+
 pub mod complex;
+pub mod convolve;
+pub mod dct;
 pub mod dft;
+pub mod fir;
+pub mod float;
+pub mod goertzel;
+pub mod hilbert;
+pub mod iir;
+pub mod resample;
+pub mod seasonality;
+pub mod sliding_dft;
+pub mod spectrum;
+pub mod stft;
+pub mod window;
+
+use std::error;
+use std::fmt;
+
+/// An error returned by a digital signal processing subroutine when its
+/// arguments do not meet its restrictions.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Error {
+    /// The input slice is empty.
+    EmptyInput,
+
+    /// The output slice has fewer elements than the subroutine writes.
+    OutputTooSmall,
+
+    /// The subroutine cannot handle inputs of this length.
+    UnsupportedLength,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            Error::EmptyInput        => "The input slice is empty",
+            Error::OutputTooSmall    => "The output slice is too small",
+            Error::UnsupportedLength => "The length is not supported",
+        })
+    }
+}
+
+impl error::Error for Error {
+}
@@ -0,0 +1,236 @@
+//! Resampling brings metrics reported at different rates onto a common,
+//! uniform rate, which the transforms in this crate require.
+//!
+//! Uniform signals are resampled by rational factors with a polyphase
+//! filter, which low-pass filters the signal to prevent aliasing without
+//! computing the samples that are thrown away. Irregularly timestamped
+//! samples are first interpolated onto a uniform grid.
+
+use dsp::fir::design;
+use dsp::fir::Response;
+use dsp::window::Window;
+
+/// The number of zero crossings of the anti-aliasing filter on either side
+/// of its centre.
+const ZERO_CROSSINGS: usize = 10;
+
+/// The method used to interpolate between samples.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Interpolation {
+    /// Connect neighbouring samples with straight lines.
+    Linear,
+
+    /// Connect neighbouring samples with cubic Hermite polynomials, whose
+    /// slopes at the samples are estimated from the neighbouring samples.
+    /// The result is smooth and passes through every sample, but may
+    /// overshoot near steps.
+    Cubic,
+}
+
+/// Resample the signal by the rational factor up / down, so that the output
+/// has up / down times as many samples, rounded up.
+///
+/// The signal is conceptually upsampled by inserting up &minus; 1 zeros
+/// between samples, low-pass filtered at the lower of the two Nyquist
+/// frequencies, and downsampled by keeping every down-th sample. The filter
+/// is centred, so the output is not delayed. The signal is assumed to be
+/// zero outside its bounds, so samples near the ends are attenuated.
+///
+/// Both factors must be at least 1. The signal may be empty.
+pub fn resample(signal: &[f64], up: usize, down: usize) -> Vec<f64> {
+    assert!( up >= 1 && down >= 1 , "The factor is zero" );
+    let divisor = gcd(up, down);
+    let (up, down) = (up / divisor, down / divisor);
+    if up == 1 && down == 1 {
+        return signal.to_vec();
+    }
+
+    let factor = usize::max(up, down);
+    let half = ZERO_CROSSINGS * factor;
+    let taps = 2 * half + 1;
+    let mut h = design(Response::LowPass(0.5 / factor as f64), taps, Window::Kaiser(5.0));
+    // The inserted zeros divide the gain by up.
+    for c in &mut h {
+        *c *= up as f64;
+    }
+
+    let n = signal.len();
+    let len = (n * up + down - 1) / down;
+    (0..len).map(|m| {
+        // Only the taps that meet an original sample contribute, which are
+        // those in the same phase as the position modulo up.
+        let t = m * down + half;
+        let mut y = 0.0;
+        let mut k = t % up;
+        while k < taps && k <= t {
+            let i = (t - k) / up;
+            if i < n {
+                y += h[k] * signal[i];
+            }
+            k += up;
+        }
+        y
+    }).collect()
+}
+
+/// Keep every factor-th sample of the signal after removing the frequencies
+/// that would alias, which are those above half the new sample rate.
+///
+/// This is [resample] with an up factor of 1. The factor must be at least 1.
+///
+/// [resample]: fn.resample.html
+pub fn decimate(signal: &[f64], factor: usize) -> Vec<f64> {
+    resample(signal, 1, factor)
+}
+
+/// Interpolate the signal at the given fractional sample index. Positions
+/// outside the signal are clamped to the first or last sample. The signal
+/// must not be empty.
+pub fn interpolate(signal: &[f64], position: f64, interpolation: Interpolation) -> f64 {
+    assert!( !signal.is_empty() , "The input slice is empty" );
+    let n = signal.len();
+    if n == 1 {
+        return signal[0];
+    }
+    let t = f64::max(0.0, f64::min(position, (n - 1) as f64));
+    let i = usize::min(t as usize, n - 2);
+    segment(|j| j as f64, signal, i, t, interpolation)
+}
+
+/// Interpolate samples taken at irregular times onto the uniform grid of
+/// len points start, start + step, start + 2 step, and so on.
+///
+/// When calling this subroutine, you must beware of certain restrictions:
+///
+///  - The times and values must have the same, nonzero length.
+///  - The times must be strictly increasing.
+///  - The step must be positive.
+///
+/// Grid points before the first or after the last time take the value of
+/// the first or last sample.
+pub fn regularize(times: &[f64], values: &[f64], start: f64, step: f64,
+                  len: usize, interpolation: Interpolation) -> Vec<f64> {
+    assert!( !times.is_empty()                       , "The input slice is empty"         );
+    assert!( times.len() == values.len()             , "The slice lengths differ"         );
+    assert!( times.windows(2).all(|w| w[0] < w[1])   , "The times are not increasing"     );
+    assert!( step > 0.0                              , "The step is not positive"         );
+
+    let n = times.len();
+    (0..len).map(|k| {
+        let t = start + k as f64 * step;
+        if n == 1 || t <= times[0] {
+            return values[0];
+        }
+        if t >= times[n - 1] {
+            return values[n - 1];
+        }
+        let i = times.partition_point(|&x| x <= t) - 1;
+        segment(|j| times[j], values, i, t, interpolation)
+    }).collect()
+}
+
+/// Interpolate between samples i and i + 1 at the given time, which lies
+/// between their times.
+fn segment<F>(time: F, values: &[f64], i: usize, t: f64, interpolation: Interpolation) -> f64
+    where F: Fn(usize) -> f64 {
+    let (t0, t1) = (time(i), time(i + 1));
+    let (v0, v1) = (values[i], values[i + 1]);
+    let h = t1 - t0;
+    let u = (t - t0) / h;
+    match interpolation {
+        Interpolation::Linear => v0 + (v1 - v0) * u,
+        Interpolation::Cubic  => {
+            let (m0, m1) = (slope(&time, values, i), slope(&time, values, i + 1));
+            let u2 = u * u;
+            let u3 = u2 * u;
+            (2.0 * u3 - 3.0 * u2 + 1.0) * v0
+                + (u3 - 2.0 * u2 + u) * h * m0
+                + (-2.0 * u3 + 3.0 * u2) * v1
+                + (u3 - u2) * h * m1
+        },
+    }
+}
+
+/// Estimate the slope at sample i with a central difference, or a one-sided
+/// difference at the first and last samples.
+fn slope<F>(time: &F, values: &[f64], i: usize) -> f64 where F: Fn(usize) -> f64 {
+    let from = i.saturating_sub(1);
+    let to = usize::min(i + 1, values.len() - 1);
+    (values[to] - values[from]) / (time(to) - time(from))
+}
+
+fn gcd(mut a: usize, mut b: usize) -> usize {
+    while b != 0 {
+        let r = a % b;
+        a = b;
+        b = r;
+    }
+    a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+    use dsp::testing::sinusoid;
+
+    #[test]
+    fn test_resample_rational() {
+        // From one sample every 10 seconds to one every 15 seconds.
+        let input = sinusoid(600, 1.0, 0.01, 1.0);
+        let output = resample(&input, 2, 3);
+        assert_eq!(output.len(), 400);
+        for (m, &y) in output.iter().enumerate().skip(30).take(340) {
+            let expected = f64::sin(2.0 * PI * 0.01 * 1.5 * m as f64);
+            assert!(f64::abs(y - expected) <= 1e-2, "{}: {} ≉ {}", m, y, expected);
+        }
+        assert_eq!(resample(&input, 4, 4), input);
+        assert_eq!(resample(&input, 3, 1).len(), 1800);
+    }
+
+    #[test]
+    fn test_decimate_prevents_aliasing() {
+        // At a quarter of the rate, the component at 0.4 cycles per sample
+        // would alias to 0.4 cycles per new sample.
+        let slow = sinusoid(2000, 1.0, 0.01, 1.0);
+        let input: Vec<f64> = slow.iter().zip(sinusoid(2000, 1.0, 0.4, 1.0)).map(|(a, b)| a + b).collect();
+        let output = decimate(&input, 4);
+        assert_eq!(output.len(), 500);
+        for (m, &y) in output.iter().enumerate().skip(20).take(460) {
+            let expected = slow[4 * m];
+            assert!(f64::abs(y - expected) <= 1e-2, "{}: {} ≉ {}", m, y, expected);
+        }
+    }
+
+    #[test]
+    fn test_interpolate() {
+        let quadratic: Vec<f64> = (0..10).map(|j| (j * j) as f64).collect();
+        assert_eq!(interpolate(&quadratic, 2.5, Interpolation::Linear), 6.5);
+        assert!(f64::abs(interpolate(&quadratic, 2.5, Interpolation::Cubic) - 6.25) <= 1e-12);
+        assert_eq!(interpolate(&quadratic, -1.0, Interpolation::Cubic), 0.0);
+        assert_eq!(interpolate(&quadratic, 20.0, Interpolation::Linear), 81.0);
+        assert_eq!(interpolate(&[5.0], 0.3, Interpolation::Cubic), 5.0);
+    }
+
+    #[test]
+    fn test_regularize() {
+        // Irregular reports of a metric that grows linearly.
+        let times  = [0.0, 7.0, 12.5, 31.0, 40.0, 58.0];
+        let values: Vec<f64> = times.iter().map(|t| 2.0 * t + 1.0).collect();
+        for &interpolation in &[Interpolation::Linear, Interpolation::Cubic] {
+            let grid = regularize(&times, &values, 0.0, 10.0, 7, interpolation);
+            assert_eq!(grid.len(), 7);
+            for (k, &v) in grid.iter().take(6).enumerate() {
+                let expected = 20.0 * k as f64 + 1.0;
+                assert!(f64::abs(v - expected) <= 1e-12, "{}: {} ≉ {}", k, v, expected);
+            }
+            assert_eq!(grid[6], 117.0);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "The times are not increasing")]
+    fn test_regularize_unordered() {
+        regularize(&[0.0, 2.0, 1.0], &[0.0; 3], 0.0, 1.0, 3, Interpolation::Linear);
+    }
+}
@@ -0,0 +1,166 @@
+//! Seasonality detection finds the periods at which a signal repeats itself,
+//! such as the daily cycle of a metric, so that thresholds can follow them.
+//!
+//! A signal that repeats with period _p_ correlates strongly with itself
+//! shifted by _p_ samples. Candidate periods are therefore the peaks of the
+//! autocorrelation function, which is computed from the power spectrum by the
+//! Wiener&ndash;Khinchin theorem.
+
+use dsp::complex::c128;
+use dsp::dft::fdft_real;
+use dsp::dft::idft_real;
+
+/// A candidate period of a signal.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Period {
+    /// The length of the period, in samples. This is interpolated between
+    /// lags and therefore need not be whole.
+    pub period: f64,
+
+    /// The confidence in [0, 1] that the signal repeats with this period,
+    /// which is the autocorrelation at the period.
+    pub confidence: f64,
+}
+
+/// Compute the normalized autocorrelation of the signal for the lags from
+/// zero up to but excluding the length of the signal.
+///
+/// The mean of the signal is removed first. Each lag is normalized by the
+/// number of overlapping samples, so that the autocorrelation of a perfectly
+/// periodic signal does not decay with the lag, and the result is divided by
+/// the variance so that lag zero has an autocorrelation of one. A constant
+/// signal has an autocorrelation of zero everywhere, and a signal with a
+/// value that is not finite one that is not a number everywhere. The signal
+/// must not be empty.
+pub fn autocorrelation(signal: &[f64]) -> Vec<f64> {
+    assert!( !signal.is_empty() , "The input slice is empty" );
+    let n = signal.len();
+    let m = (2 * n).next_power_of_two();
+
+    // Zero-padding to at least 2n keeps the circular autocorrelation that
+    // the transforms compute from wrapping around.
+    let mean = signal.iter().sum::<f64>() / n as f64;
+    let mut padded = vec![0.0; m];
+    for (p, &x) in padded.iter_mut().zip(signal) {
+        *p = x - mean;
+    }
+    let mut spectrum = vec![c128(0.0, 0.0); m / 2 + 1];
+    fdft_real(&padded, &mut spectrum);
+    for c in &mut spectrum {
+        *c = c128::from_real(c.norm_sqr());
+    }
+    idft_real(&spectrum, &mut padded);
+
+    let variance = padded[0] / n as f64;
+    if variance <= 0.0 {
+        return vec![0.0; n];
+    }
+    padded.truncate(n);
+    for (lag, r) in padded.iter_mut().enumerate() {
+        *r /= (n - lag) as f64 * variance;
+    }
+    padded
+}
+
+/// Find the candidate periods of the signal, ordered from the most to the
+/// least confident.
+///
+/// Only periods of at least two samples that fit at least twice in the
+/// signal are considered, and only those with at least the given confidence
+/// are returned. A peak at a multiple of a shorter candidate period is
+/// reported only if it is clearly more confident than the shorter period,
+/// because a signal with period _p_ also repeats with period 2_p_.
+///
+/// A signal with a value that is not finite, such as a gap marked with NaN,
+/// has no candidate periods. Fill or remove gaps first to find its periods.
+pub fn periods(signal: &[f64], min_confidence: f64) -> Vec<Period> {
+    let r = autocorrelation(signal);
+    if !r.iter().all(|r| r.is_finite()) {
+        return Vec::new();
+    }
+    let max_lag = signal.len() / 2;
+
+    let mut peaks = Vec::new();
+    for lag in 2 .. max_lag {
+        if r[lag] < min_confidence || r[lag] <= 0.0 {
+            continue;
+        }
+        if r[lag] < r[lag - 1] || r[lag] <= r[lag + 1] {
+            continue;
+        }
+        // Fit a parabola through the peak and its neighbours.
+        let (a, b, c) = (r[lag - 1], r[lag], r[lag + 1]);
+        let curvature = a - 2.0 * b + c;
+        let offset = if curvature < 0.0 { 0.5 * (a - c) / curvature } else { 0.0 };
+        peaks.push(Period{
+            period:     lag as f64 + offset,
+            confidence: f64::min(1.0, b),
+        });
+    }
+
+    let mut periods: Vec<Period> = Vec::new();
+    for peak in peaks {
+        let harmonic = periods.iter().any(|p| {
+            let k = f64::round(peak.period / p.period);
+            let tolerance = f64::max(1.0, 0.02 * peak.period);
+            k >= 2.0 && f64::abs(peak.period - k * p.period) <= tolerance
+                && peak.confidence <= p.confidence + 0.1
+        });
+        if !harmonic {
+            periods.push(peak);
+        }
+    }
+
+    periods.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    periods
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+    use dsp::testing::Random;
+
+    #[test]
+    fn test_autocorrelation() {
+        let signal: Vec<f64> = (0..64).map(|j| f64::cos(2.0 * PI * j as f64 / 8.0)).collect();
+        let r = autocorrelation(&signal);
+        assert_eq!(r.len(), 64);
+        assert!(f64::abs(r[0] - 1.0) <= 1e-9);
+        assert!(f64::abs(r[4] + 1.0) <= 1e-9);
+        assert!(f64::abs(r[8] - 1.0) <= 1e-9);
+        assert_eq!(autocorrelation(&[3.0; 10]), [0.0; 10]);
+    }
+
+    #[test]
+    fn test_periods_daily() {
+        // Hourly samples for four weeks with a daily cycle, a trend and noise.
+        let noise = Random::new(0x2545F4914F6CDD1D).noise(672);
+        let signal: Vec<f64> = (0..672).map(|j| {
+            10.0 * f64::sin(2.0 * PI * j as f64 / 24.0) + 0.01 * j as f64 + noise[j]
+        }).collect();
+        let periods = periods(&signal, 0.5);
+        assert!(!periods.is_empty());
+        assert!(f64::abs(periods[0].period - 24.0) <= 0.5, "{:?}", periods);
+        assert!(periods[0].confidence >= 0.9, "{:?}", periods);
+        // The weekly and other multiples of the daily cycle are harmonics.
+        assert_eq!(periods.len(), 1, "{:?}", periods);
+    }
+
+    #[test]
+    fn test_periods_noise() {
+        assert!(periods(&Random::new(0x2545F4914F6CDD1D).noise(1000), 0.5).is_empty());
+        assert!(periods(&[1.0; 100], 0.0).is_empty());
+    }
+
+    #[test]
+    fn test_periods_gap() {
+        let mut signal: Vec<f64> = (0..240).map(|j| f64::sin(2.0 * PI * j as f64 / 24.0)).collect();
+        assert!(!periods(&signal, 0.5).is_empty());
+        signal[100] = f64::NAN;
+        assert!(autocorrelation(&signal).iter().all(|r| r.is_nan()));
+        assert!(periods(&signal, 0.0).is_empty());
+        signal[100] = f64::INFINITY;
+        assert!(periods(&signal, 0.0).is_empty());
+    }
+}
@@ -0,0 +1,206 @@
+//! The sliding discrete Fourier transform keeps the transform of the last
+//! _n_ samples of a stream up to date, at a cost of one complex
+//! multiplication per bin per sample.
+//!
+//! When a sample enters the window and the oldest sample leaves it, bin _k_
+//! changes from _X_<sub>_k_</sub> to (_X_<sub>_k_</sub> + _x_<sub>new</sub>
+//! &minus; _x_<sub>old</sub>) e<sup>2&pi;ik/n</sup>. Rounding errors in this
+//! recurrence accumulate, so the bins are periodically recomputed from the
+//! samples in the window.
+
+use std::f64::consts::PI;
+
+use dsp::complex::c128;
+use dsp::dft::fdft_real;
+use dsp::goertzel::Goertzel;
+
+/// The transform of a sliding window over a stream of samples.
+///
+/// The bins are those of the transform of the window with its oldest sample
+/// first, as computed by [fdft]. Before the window has filled up, the missing
+/// samples count as zeros.
+///
+/// [fdft]: ../dft/fn.fdft.html
+#[derive(Clone, Debug)]
+pub struct SlidingDft {
+    /// The samples in the window, as a ring buffer whose oldest sample is at
+    /// the position.
+    window: Vec<f64>,
+    position: usize,
+
+    bins: Vec<usize>,
+    values: Vec<c128>,
+    twiddles: Vec<c128>,
+
+    resync_interval: usize,
+    since_resync: usize,
+}
+
+#[allow(clippy::len_without_is_empty)]
+impl SlidingDft {
+    /// Create a sliding transform of a window of the given length, which must
+    /// be at least 1, that keeps every bin up to date.
+    ///
+    /// The bins are recomputed after every window length of samples, which
+    /// costs as much per sample as updating the bins does.
+    pub fn new(len: usize) -> SlidingDft {
+        SlidingDft::with_bins(len, (0..len).collect())
+    }
+
+    /// Create a sliding transform of a window of the given length, which must
+    /// be at least 1, that keeps only the given bins up to date. Each bin
+    /// must be less than the length.
+    pub fn with_bins(len: usize, bins: Vec<usize>) -> SlidingDft {
+        assert!( len >= 1                        , "The length is zero"       );
+        assert!( bins.iter().all(|&k| k < len)   , "The bin is out of range"  );
+        let nf = len as f64;
+        let twiddles = bins.iter()
+            .map(|&k| c128::from_polar(1.0, 2.0 * PI * k as f64 / nf))
+            .collect();
+        SlidingDft{
+            window:          vec![0.0; len],
+            position:        0,
+            values:          vec![c128(0.0, 0.0); bins.len()],
+            bins,
+            twiddles,
+            resync_interval: len,
+            since_resync:    0,
+        }
+    }
+
+    /// Recompute the bins after every given number of samples instead. Zero
+    /// disables the periodic recomputation.
+    pub fn resync_every(mut self, interval: usize) -> SlidingDft {
+        self.resync_interval = interval;
+        self
+    }
+
+    /// The length of the window.
+    pub fn len(&self) -> usize {
+        self.window.len()
+    }
+
+    /// The bins that are kept up to date.
+    pub fn bins(&self) -> &[usize] {
+        &self.bins
+    }
+
+    /// The values of the bins that are kept up to date, in the same order.
+    pub fn values(&self) -> &[c128] {
+        &self.values
+    }
+
+    /// Slide the window by one sample.
+    pub fn process(&mut self, sample: f64) {
+        let delta = c128::from_real(sample - self.window[self.position]);
+        self.window[self.position] = sample;
+        self.position = (self.position + 1) % self.window.len();
+        for (x, &w) in self.values.iter_mut().zip(&self.twiddles) {
+            *x = (*x + delta) * w;
+        }
+
+        self.since_resync += 1;
+        if self.since_resync == self.resync_interval {
+            self.resync();
+        }
+    }
+
+    /// Slide the window by as many samples as given.
+    pub fn process_block(&mut self, samples: &[f64]) {
+        for &x in samples {
+            self.process(x);
+        }
+    }
+
+    /// Recompute the bins from the samples in the window, discarding the
+    /// rounding errors accumulated by the recurrence.
+    pub fn resync(&mut self) {
+        self.since_resync = 0;
+        let n = self.window.len();
+        let (newer, older) = self.window.split_at(self.position);
+
+        // A full transform is cheaper than a Goertzel filter per bin once
+        // more than a handful of bins is kept.
+        if self.bins.len() * 4 >= n {
+            let ordered: Vec<f64> = older.iter().chain(newer).cloned().collect();
+            let mut spectrum = vec![c128(0.0, 0.0); n / 2 + 1];
+            fdft_real(&ordered, &mut spectrum);
+            for (x, &k) in self.values.iter_mut().zip(&self.bins) {
+                *x = if k <= n / 2 { spectrum[k] } else { spectrum[n - k].conj() };
+            }
+        } else {
+            for (x, &k) in self.values.iter_mut().zip(&self.bins) {
+                let mut goertzel = Goertzel::bin(k, n);
+                goertzel.process_block(older);
+                goertzel.process_block(newer);
+                *x = goertzel.result();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dsp::dft::fdft;
+    use dsp::testing::signal;
+
+    fn expected(window: &[f64]) -> Vec<c128> {
+        let     widened  : Vec<c128> = window.iter().map(|&x| c128::from_real(x)).collect();
+        let mut spectrum = vec![c128(0.0, 0.0); window.len()];
+        fdft(&widened, &mut spectrum);
+        spectrum
+    }
+
+    #[test]
+    fn test_all_bins() {
+        let input = signal(500);
+        let mut sdft = SlidingDft::new(48).resync_every(0);
+        for (j, &x) in input.iter().enumerate() {
+            sdft.process(x);
+            if j >= 47 && j % 37 == 0 {
+                let window = &input[j - 47 ..= j];
+                for (a, b) in sdft.values().iter().zip(expected(window)) {
+                    assert!((*a - b).norm() <= 1e-9, "{}: {:?} ≉ {:?}", j, a, b);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_selected_bins() {
+        let input = signal(300);
+        let mut sdft = SlidingDft::with_bins(60, vec![0, 5, 59]);
+        sdft.process_block(&input);
+        let spectrum = expected(&input[240 ..]);
+        assert_eq!(sdft.bins(), [0, 5, 59]);
+        for (&k, a) in sdft.bins().iter().zip(sdft.values()) {
+            assert!((*a - spectrum[k]).norm() <= 1e-9, "{}: {:?} ≉ {:?}", k, a, spectrum[k]);
+        }
+    }
+
+    #[test]
+    fn test_partial_window() {
+        let mut sdft = SlidingDft::new(8);
+        sdft.process(1.0);
+        sdft.process(2.0);
+        let spectrum = expected(&[0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 2.0]);
+        for (a, b) in sdft.values().iter().zip(spectrum) {
+            assert!((*a - b).norm() <= 1e-12, "{:?} ≉ {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_resync_bounds_drift() {
+        let input = signal(200_000);
+        let mut drifting = SlidingDft::with_bins(100, vec![1, 17]).resync_every(0);
+        let mut resyncing = SlidingDft::with_bins(100, vec![1, 17]);
+        drifting.process_block(&input);
+        resyncing.process_block(&input);
+        let spectrum = expected(&input[input.len() - 100 ..]);
+        let drift = (drifting.values()[1] - spectrum[17]).norm();
+        let error = (resyncing.values()[1] - spectrum[17]).norm();
+        assert!(error <= 1e-9, "{}", error);
+        assert!(error <= drift, "{} > {}", error, drift);
+    }
+}
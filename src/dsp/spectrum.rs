@@ -0,0 +1,171 @@
+//! Power spectral density estimation reveals the frequencies at which a
+//! signal varies, such as hourly or daily cycles in a metric.
+//!
+//! All estimates are one-sided: the power of the negative frequencies is
+//! folded onto the positive ones, so that the density integrates to the
+//! variance of the signal.
+
+use dsp::complex::c128;
+use dsp::dft::fdft_real;
+use dsp::window::Window;
+
+/// A one-sided estimate of the power spectral density of a signal.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Spectrum {
+    /// The frequency of each bin, in Hz, from zero up to and including the
+    /// Nyquist frequency.
+    pub frequencies: Vec<f64>,
+
+    /// The power spectral density of each bin, in squared units of the
+    /// signal per Hz.
+    pub density: Vec<f64>,
+}
+
+impl Spectrum {
+    /// The spacing between consecutive bins, in Hz.
+    pub fn resolution(&self) -> f64 {
+        if self.frequencies.len() < 2 {
+            return 0.0;
+        }
+        self.frequencies[1] - self.frequencies[0]
+    }
+
+    /// The frequency and density of the bin with the highest density,
+    /// ignoring the zero-frequency bin. Returns nothing if there are no other
+    /// bins.
+    pub fn peak(&self) -> Option<(f64, f64)> {
+        self.frequencies.iter().zip(&self.density).skip(1)
+            .fold(None, |peak, (&f, &d)| match peak {
+                Some((_, pd)) if pd >= d => peak,
+                _                        => Some((f, d)),
+            })
+    }
+}
+
+/// Estimate the power spectral density of the signal with a single windowed
+/// transform of the whole signal.
+///
+/// The mean of the signal is removed before windowing. The signal must not
+/// be empty and the sample rate must be positive.
+pub fn periodogram(signal: &[f64], sample_rate: f64, window: Window) -> Spectrum {
+    welch(signal, sample_rate, signal.len(), 0, window)
+}
+
+/// Estimate the power spectral density of the signal with Welch&rsquo;s
+/// method: the signal is split into overlapping segments, each segment is
+/// windowed and transformed, and the resulting periodograms are averaged.
+/// This trades frequency resolution for a lower variance of the estimate.
+///
+/// When calling this subroutine, you must beware of certain restrictions:
+///
+///  - The segment length must be at least 1 and at most the length of the
+///    signal.
+///  - The overlap must be less than the segment length.
+///  - The sample rate must be positive.
+///
+/// The mean of each segment is removed before windowing. Samples at the end
+/// of the signal that do not fill a segment are ignored.
+pub fn welch(signal: &[f64], sample_rate: f64, segment_len: usize,
+             overlap: usize, window: Window) -> Spectrum {
+    assert!( segment_len >= 1             , "The segment length is zero"       );
+    assert!( segment_len <= signal.len()  , "The segment length is too large"  );
+    assert!( overlap < segment_len        , "The overlap is too large"         );
+    assert!( sample_rate > 0.0            , "The sample rate is not positive"  );
+
+    let n = segment_len;
+    let bins = n / 2 + 1;
+    let coefficients = window.periodic(n);
+    let power: f64 = coefficients.iter().map(|w| w * w).sum();
+
+    let mut density  = vec![0.0; bins];
+    let mut segment  = vec![0.0; n];
+    let mut spectrum = vec![c128(0.0, 0.0); bins];
+    let mut segments = 0;
+    let hop = n - overlap;
+    let mut start = 0;
+    while start + n <= signal.len() {
+        let input = &signal[start .. start + n];
+        let mean = input.iter().sum::<f64>() / n as f64;
+        for ((s, &x), &w) in segment.iter_mut().zip(input).zip(&coefficients) {
+            *s = (x - mean) * w;
+        }
+        fdft_real(&segment, &mut spectrum);
+        for (d, c) in density.iter_mut().zip(&spectrum) {
+            *d += c.norm_sqr();
+        }
+        segments += 1;
+        start += hop;
+    }
+
+    let scale = 1.0 / (sample_rate * power * segments as f64);
+    for (k, d) in density.iter_mut().enumerate() {
+        // Every bin except zero and, for even lengths, the Nyquist frequency
+        // has a negative-frequency twin whose power is folded onto it.
+        let twin = k != 0 && 2 * k != n;
+        *d *= if twin { 2.0 * scale } else { scale };
+    }
+
+    let frequencies = (0..bins).map(|k| k as f64 * sample_rate / n as f64).collect();
+    Spectrum{frequencies, density}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dsp::testing::sinusoid;
+
+    #[test]
+    fn test_periodogram_peak() {
+        // One sample per minute for a day, with a cycle of one per hour on
+        // top of a mean that the periodogram removes.
+        let sample_rate = 1.0 / 60.0;
+        let signal: Vec<f64> = sinusoid(1440, sample_rate, 1.0 / 3600.0, 2.0)
+            .iter().map(|x| x + 3.0).collect();
+        let spectrum = periodogram(&signal, sample_rate, Window::Hann);
+        assert_eq!(spectrum.frequencies.len(), 721);
+        assert!(f64::abs(spectrum.resolution() - sample_rate / 1440.0) <= 1e-15);
+        let (frequency, _) = spectrum.peak().unwrap();
+        assert!(f64::abs(frequency - 1.0 / 3600.0) <= 1e-12, "{}", frequency);
+    }
+
+    #[test]
+    fn test_periodogram_parseval() {
+        // With the rectangular window, the density integrates to the
+        // variance, which is a²/2 for a sinusoid of amplitude a.
+        let signal = sinusoid(256, 8.0, 1.0, 3.0);
+        let spectrum = periodogram(&signal, 8.0, Window::Rectangular);
+        let total: f64 = spectrum.density.iter().sum::<f64>() * spectrum.resolution();
+        assert!(f64::abs(total - 4.5) <= 1e-9, "{}", total);
+    }
+
+    #[test]
+    fn test_periodogram_single_sample() {
+        // A single sample has no variance once its mean is removed, whatever
+        // the window.
+        for &window in &[Window::Rectangular, Window::Hann, Window::Blackman] {
+            let spectrum = periodogram(&[5.0], 2.0, window);
+            assert_eq!(spectrum.frequencies, vec![0.0]);
+            assert_eq!(spectrum.density, vec![0.0], "{:?}", window);
+        }
+    }
+
+    #[test]
+    fn test_welch() {
+        let signal = sinusoid(4096, 100.0, 12.5, 1.0);
+        let spectrum = welch(&signal, 100.0, 256, 128, Window::Hann);
+        assert_eq!(spectrum.frequencies.len(), 129);
+        let (frequency, density) = spectrum.peak().unwrap();
+        assert!(f64::abs(frequency - 12.5) <= 1e-9, "{}", frequency);
+        // The Hann window spreads the power over three bins, but the density
+        // still integrates to the variance of the sinusoid.
+        let total: f64 = spectrum.density.iter().sum::<f64>() * spectrum.resolution();
+        assert!(f64::abs(total - 0.5) <= 1e-3, "{}", total);
+        assert!(density > 100.0 * spectrum.density[64]);
+    }
+
+    #[test]
+    #[should_panic(expected = "The overlap is too large")]
+    fn test_welch_overlap_too_large() {
+        welch(&[0.0; 16], 1.0, 8, 8, Window::Hann);
+    }
+}
@@ -0,0 +1,158 @@
+//! The short-time Fourier transform shows how the frequency content of a
+//! signal changes over time, such as a cycle that appears only during
+//! business hours.
+//!
+//! The signal is split into overlapping frames that are windowed and
+//! transformed separately. Frame _k_ is centred on sample _k_ &middot; hop, and
+//! the signal is padded with zeros at both ends so that the first and last
+//! samples are covered as well as the others. The inverse transform
+//! reassembles the frames by weighted overlap-add.
+
+use dsp::complex::c128;
+use dsp::dft::fdft;
+use dsp::dft::idft;
+use dsp::window::Window;
+
+/// Compute the short-time Fourier transform of the signal.
+///
+/// Each frame holds all `frame_len` bins of the transform of the windowed
+/// frame, as computed by [fdft]. There are just enough frames for every
+/// sample to lie in at least one of them.
+///
+/// When calling this subroutine, you must beware of certain restrictions:
+///
+///  - The signal must not be empty.
+///  - The frame length must be at least 1.
+///  - The hop must be at least 1 and at most the frame length.
+///
+/// [fdft]: ../dft/fn.fdft.html
+pub fn stft(signal: &[f64], frame_len: usize, hop: usize, window: Window) -> Vec<Vec<c128>> {
+    assert!( !signal.is_empty()           , "The input slice is empty"    );
+    assert!( frame_len >= 1               , "The frame length is zero"    );
+    assert!( hop >= 1 && hop <= frame_len , "The hop is out of range"     );
+
+    let n = frame_len;
+    let coefficients = window.periodic(n);
+    let mut frame = vec![c128(0.0, 0.0); n];
+    // The last frame must reach the last sample, and each frame reaches
+    // n - n / 2 samples past its centre.
+    let frames = (signal.len().saturating_sub(n - n / 2) + hop - 1) / hop + 1;
+    (0 .. frames).map(|k| {
+        let start = (k * hop) as isize - (n / 2) as isize;
+        for (j, (f, &w)) in frame.iter_mut().zip(&coefficients).enumerate() {
+            let x = sample(signal, start + j as isize);
+            *f = c128::from_real(x * w);
+        }
+        let mut spectrum = vec![c128(0.0, 0.0); n];
+        fdft(&frame, &mut spectrum);
+        spectrum
+    }).collect()
+}
+
+/// Compute the inverse short-time Fourier transform of frames computed by
+/// [stft] with the given hop and window, and return the first `len` samples
+/// of the signal.
+///
+/// Overlapping frames are summed with the window as weight and divided by
+/// the sum of the squared window, which recovers the signal exactly from
+/// unmodified frames and gives the least-squares estimate from modified
+/// ones. Samples that no frame covers with a nonzero weight are zero.
+///
+/// The frames must not be empty and must all have the same length, and the
+/// hop must satisfy the same restrictions as for [stft].
+///
+/// [stft]: fn.stft.html
+pub fn istft(frames: &[Vec<c128>], hop: usize, window: Window, len: usize) -> Vec<f64> {
+    assert!( !frames.is_empty()           , "The frame slice is empty"         );
+    let n = frames[0].len();
+    assert!( n >= 1                       , "The frame length is zero"         );
+    assert!( frames.iter().all(|f| f.len() == n) , "The frame lengths differ"  );
+    assert!( hop >= 1 && hop <= n         , "The hop is out of range"          );
+
+    let coefficients = window.periodic(n);
+    let mut output  = vec![0.0; len];
+    let mut weights = vec![0.0; len];
+    let mut frame   = vec![c128(0.0, 0.0); n];
+    for (k, spectrum) in frames.iter().enumerate() {
+        idft(spectrum, &mut frame);
+        let start = (k * hop) as isize - (n / 2) as isize;
+        for (j, (f, &w)) in frame.iter().zip(&coefficients).enumerate() {
+            let i = start + j as isize;
+            if i >= 0 && (i as usize) < len {
+                output[i as usize]  += f.real() * w;
+                weights[i as usize] += w * w;
+            }
+        }
+    }
+
+    for (o, &w) in output.iter_mut().zip(&weights) {
+        *o = if w > 1e-12 { *o / w } else { 0.0 };
+    }
+    output
+}
+
+/// Compute the magnitude spectrogram of the signal: for every frame of the
+/// [stft], the magnitudes of the bins from zero up to and including the
+/// Nyquist frequency, that is frame_len / 2 + 1 bins.
+///
+/// The same restrictions apply as those to the [stft] subroutine.
+///
+/// [stft]: fn.stft.html
+pub fn spectrogram(signal: &[f64], frame_len: usize, hop: usize, window: Window) -> Vec<Vec<f64>> {
+    stft(signal, frame_len, hop, window).iter()
+        .map(|frame| frame[.. frame_len / 2 + 1].iter().map(|c| c.norm()).collect())
+        .collect()
+}
+
+/// The sample at the given index, or zero outside the signal.
+fn sample(signal: &[f64], index: isize) -> f64 {
+    if index < 0 {
+        return 0.0;
+    }
+    signal.get(index as usize).cloned().unwrap_or(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+    use dsp::testing::signal;
+
+    #[test]
+    fn test_roundtrip() {
+        for &(frame_len, hop, window) in &[(64, 16, Window::Hann),
+                                           (60, 20, Window::Hamming),
+                                           (32, 32, Window::Rectangular),
+                                           (50, 7, Window::Blackman)] {
+            let input  = signal(500);
+            let frames = stft(&input, frame_len, hop, window);
+            let last = (frames.len() - 1) * hop;
+            assert!(last + frame_len - frame_len / 2 >= 500 && last < 500 + hop);
+            let output = istft(&frames, hop, window, input.len());
+            for (j, (a, b)) in output.iter().zip(&input).enumerate() {
+                assert!(f64::abs(a - b) <= 1e-9, "{}, {}: {} ≉ {}", frame_len, j, a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_spectrogram_follows_frequency() {
+        // A cycle of 16 samples in the first half and of 4 in the second.
+        let input: Vec<f64> = (0..1024).map(|j| {
+            let period = if j < 512 { 16.0 } else { 4.0 };
+            f64::sin(2.0 * PI * j as f64 / period)
+        }).collect();
+        let magnitudes = spectrogram(&input, 64, 32, Window::Hann);
+        assert_eq!(magnitudes.len(), 32);
+        assert!(magnitudes.iter().all(|m| m.len() == 33));
+        let peak = |m: &Vec<f64>| (0..m.len()).fold(0, |p, k| if m[k] > m[p] { k } else { p });
+        assert_eq!(peak(&magnitudes[4]), 4);
+        assert_eq!(peak(&magnitudes[28]), 16);
+    }
+
+    #[test]
+    #[should_panic(expected = "The hop is out of range")]
+    fn test_hop_too_large() {
+        stft(&[1.0; 10], 4, 5, Window::Hann);
+    }
+}
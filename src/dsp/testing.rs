@@ -0,0 +1,135 @@
+//! Assertions and test signals shared by the tests of the digital signal
+//! processing subroutines.
+
+use std::f64::consts::PI;
+
+use dsp::complex::Complex;
+use dsp::complex::c128;
+
+/// Assert that two real or complex numbers are approximately equal, that is
+/// that their real parts and their imaginary parts each differ by at most
+/// the tolerance. Further arguments are formatted in front of the failure
+/// message.
+macro_rules! assert_aq {
+    ($a:expr, $b:expr, $tolerance:expr) => {
+        assert_aq!($a, $b, $tolerance, "")
+    };
+    ($a:expr, $b:expr, $tolerance:expr, $($context:tt)+) => {{
+        let (a, b) = ($a, $b);
+        let tolerance: f64 = $tolerance;
+        let ((ar, ai), (br, bi)) = (::dsp::testing::Parts::parts(a),
+                                    ::dsp::testing::Parts::parts(b));
+        assert!(f64::abs(ar - br) <= tolerance && f64::abs(ai - bi) <= tolerance,
+                "{}{:?} ≉ {:?}", format!($($context)+), a, b);
+    }};
+}
+
+/// A number split into its real and imaginary parts, widened to `f64`.
+pub trait Parts {
+    fn parts(self) -> (f64, f64);
+}
+
+impl Parts for f32 {
+    fn parts(self) -> (f64, f64) {
+        (f64::from(self), 0.0)
+    }
+}
+
+impl Parts for f64 {
+    fn parts(self) -> (f64, f64) {
+        (self, 0.0)
+    }
+}
+
+impl Parts for Complex<f32> {
+    fn parts(self) -> (f64, f64) {
+        (f64::from(self.real()), f64::from(self.imag()))
+    }
+}
+
+impl Parts for Complex<f64> {
+    fn parts(self) -> (f64, f64) {
+        (self.real(), self.imag())
+    }
+}
+
+/// A real test signal: a cycle of 5 samples and a slower cosine on top of a
+/// mean of 3.
+pub fn signal(n: usize) -> Vec<f64> {
+    (0..n).map(|j| {
+        let t = j as f64;
+        f64::sin(2.0 * PI * t / 5.0) + 0.5 * f64::cos(0.3 * t) + 3.0
+    }).collect()
+}
+
+/// A complex test signal whose parts have different frequencies, and whose
+/// imaginary part has a trend.
+pub fn complex_signal(n: usize) -> Vec<c128> {
+    (0..n).map(|j| {
+        let t = j as f64;
+        c128(f64::sin(0.3 * t) + 0.5, f64::cos(1.7 * t) - 0.25 * t / 7.0)
+    }).collect()
+}
+
+/// The sinusoid of the given frequency and amplitude, sampled at the given
+/// rate.
+pub fn sinusoid(n: usize, sample_rate: f64, frequency: f64, amplitude: f64) -> Vec<f64> {
+    (0..n).map(|j| {
+        let t = j as f64 / sample_rate;
+        amplitude * f64::sin(2.0 * PI * frequency * t)
+    }).collect()
+}
+
+/// The transform computed straight from the definition, with the
+/// exponent reduced modulo n and compensated summation, so that its own
+/// error is negligible.
+pub fn naive_dft(input: &[c128]) -> Vec<c128> {
+    let n = input.len();
+    (0..n).map(|k| {
+        let (mut sum, mut compensation) = (c128(0.0, 0.0), c128(0.0, 0.0));
+        for (j, &x) in input.iter().enumerate() {
+            let term = x * c128::from_polar(1.0, -2.0 * PI * ((j * k) % n) as f64 / n as f64)
+                - compensation;
+            let next = sum + term;
+            compensation = (next - sum) - term;
+            sum = next;
+        }
+        sum
+    }).collect()
+}
+
+/// A xorshift generator, so that failures are reproducible.
+pub struct Random(u64);
+
+impl Random {
+    /// The seed must not be zero.
+    pub fn new(seed: u64) -> Random {
+        Random(seed)
+    }
+
+    pub fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// A number in [-1, 1).
+    pub fn float(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 52) as f64 - 1.0
+    }
+
+    pub fn complex(&mut self) -> c128 {
+        c128(self.float(), self.float())
+    }
+
+    /// Real white noise in [-1, 1).
+    pub fn noise(&mut self, n: usize) -> Vec<f64> {
+        (0..n).map(|_| self.float()).collect()
+    }
+
+    /// Complex white noise with both parts in [-1, 1).
+    pub fn signal(&mut self, n: usize) -> Vec<c128> {
+        (0..n).map(|_| self.complex()).collect()
+    }
+}
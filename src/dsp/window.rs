@@ -0,0 +1,248 @@
+//! Window functions taper a signal towards its ends before it is
+//! transformed, which reduces the spectral leakage caused by the implicit
+//! rectangular window.
+//!
+//! Every window comes in two flavors. The _symmetric_ flavor has equal first
+//! and last coefficients and is meant for filter design. The _periodic_
+//! flavor is the symmetric window of one more coefficient with the last one
+//! dropped, and is meant for spectral analysis.
+
+use std::f64::consts::PI;
+
+use dsp::complex::c128;
+
+/// A window function.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Window {
+    /// The rectangular window, which does not taper at all.
+    Rectangular,
+
+    /// The Hann window, a raised cosine that reaches zero at both ends.
+    Hann,
+
+    /// The Hamming window, a raised cosine optimized to suppress the nearest
+    /// side lobe.
+    Hamming,
+
+    /// The three-term Blackman window.
+    Blackman,
+
+    /// The four-term Blackman&ndash;Harris window, with side lobes below
+    /// &minus;92 dB.
+    BlackmanHarris,
+
+    /// The Kaiser window with the given shape parameter &beta;. Larger values
+    /// trade a wider main lobe for lower side lobes; zero gives the
+    /// rectangular window.
+    Kaiser(f64),
+
+    /// The flat-top window, which has a very flat main lobe and is therefore
+    /// suited to measuring amplitudes.
+    FlatTop,
+
+    /// The Tukey window with the given taper ratio &alpha; in [0, 1]. Zero
+    /// gives the rectangular window and one gives the Hann window.
+    Tukey(f64),
+}
+
+impl Window {
+    /// The symmetric coefficients of the window of the given length. The
+    /// window of length one is the single coefficient one.
+    pub fn symmetric(self, len: usize) -> Vec<f64> {
+        if len <= 1 {
+            return vec![1.0; len];
+        }
+        let span = (len - 1) as f64;
+        (0..len).map(|k| self.at(k as f64 / span)).collect()
+    }
+
+    /// The periodic coefficients of the window of the given length. As for
+    /// the symmetric flavor, the window of length one is the single
+    /// coefficient one rather than the first coefficient of the window of
+    /// length two, which is zero for many windows.
+    pub fn periodic(self, len: usize) -> Vec<f64> {
+        if len <= 1 {
+            return vec![1.0; len];
+        }
+        let span = len as f64;
+        (0..len).map(|k| self.at(k as f64 / span)).collect()
+    }
+
+    /// Multiply the signal by the periodic coefficients of the window of the
+    /// same length.
+    pub fn apply(self, signal: &mut [f64]) {
+        let coefficients = self.periodic(signal.len());
+        for (x, w) in signal.iter_mut().zip(coefficients) {
+            *x *= w;
+        }
+    }
+
+    /// Multiply the complex signal by the periodic coefficients of the window
+    /// of the same length.
+    pub fn apply_complex(self, signal: &mut [c128]) {
+        let coefficients = self.periodic(signal.len());
+        for (x, w) in signal.iter_mut().zip(coefficients) {
+            *x *= w;
+        }
+    }
+
+    /// The coherent gain of the periodic window of the given length, which is
+    /// the mean of its coefficients. Dividing the magnitude of a windowed
+    /// spectrum by the coherent gain corrects the amplitude of sinusoids.
+    /// The length must be at least 1.
+    pub fn coherent_gain(self, len: usize) -> f64 {
+        assert!(len >= 1, "The length is zero");
+        self.periodic(len).iter().sum::<f64>() / len as f64
+    }
+
+    /// The coefficient at the relative position _x_ in [0, 1] of the window.
+    fn at(self, x: f64) -> f64 {
+        match self {
+            Window::Rectangular    => 1.0,
+            Window::Hann           => cosine_sum(&[0.5, 0.5], x),
+            Window::Hamming        => cosine_sum(&[0.54, 0.46], x),
+            Window::Blackman       => cosine_sum(&[0.42, 0.5, 0.08], x),
+            Window::BlackmanHarris => cosine_sum(&[0.35875, 0.48829,
+                                                  0.14128, 0.01168], x),
+            Window::FlatTop        => cosine_sum(&[0.21557895, 0.41663158,
+                                                  0.277263158, 0.083578947,
+                                                  0.006947368], x),
+            Window::Kaiser(beta) => {
+                let t = 2.0 * x - 1.0;
+                bessel_i0(beta * f64::sqrt(f64::max(0.0, 1.0 - t * t)))
+                    / bessel_i0(beta)
+            },
+            Window::Tukey(alpha) => {
+                if alpha <= 0.0 {
+                    return 1.0;
+                }
+                let edge = f64::min(x, 1.0 - x);
+                if edge >= alpha / 2.0 {
+                    1.0
+                } else {
+                    0.5 - 0.5 * f64::cos(2.0 * PI * edge / alpha)
+                }
+            },
+        }
+    }
+}
+
+/// The generalized cosine window
+/// _a_<sub>0</sub> &minus; _a_<sub>1</sub> cos 2&pi;_x_ +
+/// _a_<sub>2</sub> cos 4&pi;_x_ &minus; &hellip;
+fn cosine_sum(a: &[f64], x: f64) -> f64 {
+    a.iter().enumerate().map(|(k, &a)| {
+        let sign = if k % 2 == 0 { 1.0 } else { -1.0 };
+        sign * a * f64::cos(2.0 * PI * k as f64 * x)
+    }).sum()
+}
+
+/// The zeroth-order modified Bessel function of the first kind, computed
+/// from its power series.
+fn bessel_i0(x: f64) -> f64 {
+    let (mut sum, mut term, half) = (1.0, 1.0, x / 2.0);
+    for k in 1.. {
+        let r = half / k as f64;
+        term *= r * r;
+        sum += term;
+        if term <= sum * 1e-17 {
+            break;
+        }
+    }
+    sum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOWS: [Window; 8] = [Window::Rectangular, Window::Hann,
+                                  Window::Hamming, Window::Blackman,
+                                  Window::BlackmanHarris, Window::Kaiser(8.6),
+                                  Window::FlatTop, Window::Tukey(0.5)];
+
+    #[test]
+    fn test_symmetric() {
+        let hann = Window::Hann.symmetric(5);
+        for (&a, &b) in hann.iter().zip(&[0.0, 0.5, 1.0, 0.5, 0.0]) {
+            assert_aq!(a, b, 1e-9);
+        }
+        for &window in &WINDOWS {
+            let w = window.symmetric(33);
+            // The flat-top coefficients are rounded and sum to 1 + 3e-9.
+            assert!(f64::abs(w[16] - 1.0) <= 1e-8, "{:?}: {:?}", window, w[16]);
+            for k in 0..33 {
+                assert_aq!(w[k], w[32 - k], 1e-9);
+            }
+            assert_eq!(window.symmetric(1), [1.0]);
+        }
+    }
+
+    #[test]
+    fn test_periodic() {
+        for &window in &WINDOWS {
+            let p = window.periodic(32);
+            let s = window.symmetric(33);
+            for (&a, &b) in p.iter().zip(&s) {
+                assert_aq!(a, b, 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn test_special_cases() {
+        let rect = Window::Rectangular.symmetric(16);
+        let hann = Window::Hann.symmetric(16);
+        for (k, &r) in rect.iter().enumerate() {
+            assert_aq!(Window::Kaiser(0.0).symmetric(16)[k], r, 1e-9);
+            assert_aq!(Window::Tukey(0.0).symmetric(16)[k], r, 1e-9);
+            assert_aq!(Window::Tukey(1.0).symmetric(16)[k], hann[k], 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_short() {
+        for &window in &[Window::Rectangular, Window::Hann, Window::Blackman,
+                         Window::Kaiser(5.0), Window::Tukey(0.5)] {
+            assert_eq!(window.symmetric(0), []);
+            assert_eq!(window.periodic(0), []);
+            assert_eq!(window.symmetric(1), [1.0]);
+            assert_eq!(window.periodic(1), [1.0]);
+            assert_eq!(window.coherent_gain(1), 1.0);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "The length is zero")]
+    fn test_coherent_gain_empty() {
+        Window::Hann.coherent_gain(0);
+    }
+
+    #[test]
+    fn test_coherent_gain() {
+        assert_aq!(Window::Rectangular.coherent_gain(64), 1.0, 1e-9);
+        assert_aq!(Window::Hann.coherent_gain(64), 0.5, 1e-9);
+        assert_aq!(Window::Hamming.coherent_gain(64), 0.54, 1e-9);
+        assert_aq!(Window::Blackman.coherent_gain(64), 0.42, 1e-9);
+        assert_aq!(Window::Tukey(0.5).coherent_gain(64), 0.75, 1e-9);
+    }
+
+    #[test]
+    fn test_apply() {
+        let mut signal = vec![2.0; 4];
+        Window::Hann.apply(&mut signal);
+        for (&a, &b) in signal.iter().zip(&[0.0, 1.0, 2.0, 1.0]) {
+            assert_aq!(a, b, 1e-9);
+        }
+        let mut signal = vec![c128(0.0, 2.0); 4];
+        Window::Hann.apply_complex(&mut signal);
+        assert_aq!(signal[1].imag(), 1.0, 1e-9);
+    }
+
+    #[test]
+    fn test_bessel_i0() {
+        assert_aq!(bessel_i0(0.0), 1.0, 1e-9);
+        assert_aq!(bessel_i0(1.0), 1.2660658777520082, 1e-9);
+        assert!(f64::abs(bessel_i0(10.0) / 2815.716628466254 - 1.0) <= 1e-12);
+    }
+}